@@ -0,0 +1,180 @@
+use crate::matrix::Mat4;
+use crate::ray::Ray;
+use crate::tuple::Point;
+use crate::EPSILON;
+
+/// An axis-aligned bounding box, described by its [min: Point] and
+/// [max: Point] corners. Shapes that extend forever (like planes) have
+/// infinite components. An empty box has min greater than max, so that
+/// adding anything to it gives back the other box
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Bounds {
+    pub min: Point,
+    pub max: Point,
+}
+
+impl Bounds {
+    pub fn new(min: Point, max: Point) -> Self {
+        Self { min, max }
+    }
+
+    /// A box that contains nothing
+    pub fn empty() -> Self {
+        Self {
+            min: Point::new(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+            max: Point::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+        }
+    }
+
+    /// A box that contains the whole space
+    pub fn infinite() -> Self {
+        Self {
+            min: Point::new(f64::NEG_INFINITY, f64::NEG_INFINITY, f64::NEG_INFINITY),
+            max: Point::new(f64::INFINITY, f64::INFINITY, f64::INFINITY),
+        }
+    }
+
+    /// The box of the default (unit) sphere and cube: -1 to +1 along each axis
+    pub fn unit() -> Self {
+        Self::new(Point::new(-1.0, -1.0, -1.0), Point::new(1.0, 1.0, 1.0))
+    }
+
+    /// Return true if the box contains nothing
+    pub fn is_empty(&self) -> bool {
+        self.min.x > self.max.x || self.min.y > self.max.y || self.min.z > self.max.z
+    }
+
+    /// Return true if the box is not empty and doesn't extend forever
+    pub fn is_finite(&self) -> bool {
+        !self.is_empty()
+            && [self.min.x, self.min.y, self.min.z, self.max.x, self.max.y, self.max.z]
+                .iter()
+                .all(|c| c.is_finite())
+    }
+
+    /// Grow the box so that it contains the given point
+    pub fn add_point(&mut self, p: Point) {
+        self.min = Point::new(self.min.x.min(p.x), self.min.y.min(p.y), self.min.z.min(p.z));
+        self.max = Point::new(self.max.x.max(p.x), self.max.y.max(p.y), self.max.z.max(p.z));
+    }
+
+    /// Return the smallest box containing both this box and the other one
+    pub fn merge(&self, other: &Bounds) -> Bounds {
+        let mut out = *self;
+        if !other.is_empty() {
+            out.add_point(other.min);
+            out.add_point(other.max);
+        }
+        out
+    }
+
+    /// Return true if the point lies inside the box (borders included)
+    pub fn contains_point(&self, p: Point) -> bool {
+        self.min.x <= p.x && p.x <= self.max.x
+            && self.min.y <= p.y && p.y <= self.max.y
+            && self.min.z <= p.z && p.z <= self.max.z
+    }
+
+    /// Return the point in the middle of the box
+    pub fn centroid(&self) -> Point {
+        Point::new(
+            (self.min.x + self.max.x) / 2.0,
+            (self.min.y + self.max.y) / 2.0,
+            (self.min.z + self.max.z) / 2.0,
+        )
+    }
+
+    /// Return the area of the six faces of the box. Used by the surface
+    /// area heuristic to estimate how likely a ray is to hit the box
+    pub fn surface_area(&self) -> f64 {
+        if self.is_empty() {
+            return 0.0;
+        }
+        let d = self.max - self.min;
+        2.0 * (d.x * d.y + d.y * d.z + d.z * d.x)
+    }
+
+    /// This function transforms the eight corners of the box with the
+    /// given matrix and returns the box containing all of them. Infinite
+    /// components are kept infinite instead of turning into NaN
+    pub fn transform(&self, m: &Mat4) -> Bounds {
+        if self.is_empty() {
+            return *self;
+        }
+        let mut out = Bounds::empty();
+        for &x in [self.min.x, self.max.x].iter() {
+            for &y in [self.min.y, self.max.y].iter() {
+                for &z in [self.min.z, self.max.z].iter() {
+                    out.add_point(transform_corner(m, [x, y, z]));
+                }
+            }
+        }
+        // an axis mixing +inf and -inf has no meaningful limit
+        let fix = |min: f64, max: f64| {
+            if min.is_nan() || max.is_nan() {
+                (f64::NEG_INFINITY, f64::INFINITY)
+            } else {
+                (min, max)
+            }
+        };
+        let (min_x, max_x) = fix(out.min.x, out.max.x);
+        let (min_y, max_y) = fix(out.min.y, out.max.y);
+        let (min_z, max_z) = fix(out.min.z, out.max.z);
+        Bounds::new(Point::new(min_x, min_y, min_z), Point::new(max_x, max_y, max_z))
+    }
+
+    /// This function tests the ray against the box using the same slab method
+    /// of the cube, and returns the (tmin, tmax) range where the ray is inside
+    /// the box. Negative values are kept, since what matters is the whole line
+    pub fn intersect(&self, ray: Ray) -> Option<(f64, f64)> {
+        let (xtmin, xtmax) = check_axis(ray.origin.x, ray.direction.x, self.min.x, self.max.x)?;
+        let (ytmin, ytmax) = check_axis(ray.origin.y, ray.direction.y, self.min.y, self.max.y)?;
+        let (ztmin, ztmax) = check_axis(ray.origin.z, ray.direction.z, self.min.z, self.max.z)?;
+
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+        if tmin > tmax {
+            None
+        } else {
+            Some((tmin, tmax))
+        }
+    }
+
+    /// Return true if the line along the ray crosses the box
+    pub fn intersects(&self, ray: Ray) -> bool {
+        self.intersect(ray).is_some()
+    }
+}
+
+/// This function multiplies the point [c] by the matrix treating
+/// zero coefficients as zero even when the coordinate is infinite
+fn transform_corner(m: &Mat4, c: [f64; 3]) -> Point {
+    let mut out = [0.0; 3];
+    for (row, o) in out.iter_mut().enumerate() {
+        *o = m[(row, 3)];
+        for (col, v) in c.iter().enumerate() {
+            if m[(row, col)] != 0.0 {
+                *o += m[(row, col)] * v;
+            }
+        }
+    }
+    Point::new(out[0], out[1], out[2])
+}
+
+/// This function returns the range of t where the ray is between [min] and
+/// [max] along one axis, or [None] if a ray parallel to the axis is outside
+fn check_axis(origin: f64, direction: f64, min: f64, max: f64) -> Option<(f64, f64)> {
+    if direction.abs() < EPSILON {
+        if origin < min || origin > max {
+            return None;
+        }
+        return Some((f64::NEG_INFINITY, f64::INFINITY));
+    }
+    let tmin = (min - origin) / direction;
+    let tmax = (max - origin) / direction;
+    if tmin > tmax {
+        Some((tmax, tmin))
+    } else {
+        Some((tmin, tmax))
+    }
+}
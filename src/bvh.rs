@@ -0,0 +1,244 @@
+use crate::bounds::Bounds;
+use crate::intersection::Intersection;
+use crate::ray::Ray;
+use crate::shape::Shape;
+use crate::tuple::Point;
+
+/// Nodes with this many shapes or less are never split
+const MIN_LEAF_SIZE: usize = 2;
+/// Nodes with more shapes than this are always split, even if the
+/// surface area heuristic says a leaf would be cheaper
+const MAX_LEAF_SIZE: usize = 8;
+/// Number of buckets the centroids are sorted in to evaluate the splits
+const BUCKETS: usize = 12;
+/// Cost of visiting an interior node, relative to intersecting a shape
+const TRAVERSAL_COST: f64 = 0.125;
+
+/// A node of the hierarchy. Leaves own the shapes from [start] to
+/// [start + count] of the indices list, interior nodes have [count] 0
+/// and point to their [left] and [right] children
+#[derive(Debug, Clone)]
+struct BvhNode {
+    bounds: Bounds,
+    left: usize,
+    right: usize,
+    start: usize,
+    count: usize,
+}
+
+/// The shape being sorted in the hierarchy while it is built
+#[derive(Debug, Clone, Copy)]
+struct Primitive {
+    index: usize,
+    bounds: Bounds,
+    centroid: Point,
+}
+
+/// A bounding volume hierarchy over a list of shapes. It doesn't own
+/// the shapes, it only stores their indices: the same list used to build
+/// it must be passed to [intersect]. Shapes without finite bounds (planes)
+/// can't be sorted in the tree, so they are kept aside and always tested.
+/// The tree is split with the surface area heuristic (SAH): among a set of
+/// candidate planes it picks the one that minimizes the expected cost of
+/// intersecting both halves.
+#[derive(Debug, Clone)]
+pub struct Bvh {
+    nodes: Vec<BvhNode>,
+    indices: Vec<usize>,
+    unbounded: Vec<usize>,
+    bounds: Bounds,
+    len: usize,
+}
+
+impl Bvh {
+    /// Build the hierarchy over the given shapes, using
+    /// the bounds of each shape in their parent space
+    pub fn build(shapes: &[Box<dyn Shape>]) -> Self {
+        let mut bvh = Bvh {
+            nodes: vec![],
+            indices: vec![],
+            unbounded: vec![],
+            bounds: Bounds::empty(),
+            len: shapes.len(),
+        };
+        let mut primitives = vec![];
+        for (index, shape) in shapes.iter().enumerate() {
+            let bounds = shape.bounds();
+            bvh.bounds = bvh.bounds.merge(&bounds);
+            if bounds.is_finite() {
+                primitives.push(Primitive { index, bounds, centroid: bounds.centroid() });
+            } else if !bounds.is_empty() {
+                bvh.unbounded.push(index);
+            }
+        }
+        if !primitives.is_empty() {
+            bvh.build_node(&mut primitives);
+        }
+        bvh
+    }
+
+    /// Return the number of shapes the hierarchy was built on
+    pub fn len(&self) -> usize {
+        self.len
+    }
+
+    /// Return true if the hierarchy was built on no shapes
+    pub fn is_empty(&self) -> bool {
+        self.len == 0
+    }
+
+    /// Return the box containing all the shapes
+    pub fn bounds(&self) -> Bounds {
+        self.bounds
+    }
+
+    /// Return the number of nodes in the tree
+    pub fn node_count(&self) -> usize {
+        self.nodes.len()
+    }
+
+    /// This function intersects the ray with the shapes whose boxes it
+    /// crosses, skipping every branch of the tree the ray misses. The
+    /// [shapes] must be the same list used to build the hierarchy
+    pub fn intersect(&self, shapes: &[Box<dyn Shape>], ray: Ray) -> Vec<Intersection> {
+        let mut xs = vec![];
+        for &i in self.unbounded.iter() {
+            xs.extend(shapes[i].intersect(ray).xs);
+        }
+        if self.nodes.is_empty() {
+            return xs;
+        }
+        let mut stack = vec![0];
+        while let Some(idx) = stack.pop() {
+            let node = &self.nodes[idx];
+            if !node.bounds.intersects(ray) {
+                continue;
+            }
+            if node.count > 0 {
+                for &i in self.indices[node.start..node.start + node.count].iter() {
+                    xs.extend(shapes[i].intersect(ray).xs);
+                }
+            } else {
+                stack.push(node.right);
+                stack.push(node.left);
+            }
+        }
+        xs
+    }
+
+    /// Push a leaf holding the given primitives and return its index
+    fn push_leaf(&mut self, primitives: &[Primitive], bounds: Bounds) -> usize {
+        let start = self.indices.len();
+        self.indices.extend(primitives.iter().map(|p| p.index));
+        self.nodes.push(BvhNode { bounds, left: 0, right: 0, start, count: primitives.len() });
+        self.nodes.len() - 1
+    }
+
+    /// This function recursively builds the node for the given primitives
+    /// and returns its index in the nodes list
+    fn build_node(&mut self, primitives: &mut [Primitive]) -> usize {
+        let bounds = primitives.iter().fold(Bounds::empty(), |b, p| b.merge(&p.bounds));
+        let n = primitives.len();
+        if n <= MIN_LEAF_SIZE {
+            return self.push_leaf(primitives, bounds);
+        }
+
+        // split along the axis where the centroids are most spread out
+        let mut centroids = Bounds::empty();
+        for p in primitives.iter() {
+            centroids.add_point(p.centroid);
+        }
+        let extent = centroids.max - centroids.min;
+        let axis = if extent.x >= extent.y && extent.x >= extent.z {
+            0
+        } else if extent.y >= extent.z {
+            1
+        } else {
+            2
+        };
+        let (cmin, cmax) = (axis_of(centroids.min, axis), axis_of(centroids.max, axis));
+        if cmax - cmin <= 0.0 {
+            // all the centroids sit at the same place, nothing to split
+            return self.push_leaf(primitives, bounds);
+        }
+
+        // sort the centroids in buckets and evaluate the cost of
+        // splitting after each bucket: the chance of hitting each half
+        // is proportional to its surface area
+        let bucket_of = |p: &Primitive| {
+            let b = ((axis_of(p.centroid, axis) - cmin) / (cmax - cmin) * BUCKETS as f64) as usize;
+            b.min(BUCKETS - 1)
+        };
+        let mut counts = [0usize; BUCKETS];
+        let mut boxes = [Bounds::empty(); BUCKETS];
+        for p in primitives.iter() {
+            let b = bucket_of(p);
+            counts[b] += 1;
+            boxes[b] = boxes[b].merge(&p.bounds);
+        }
+        let mut best = (f64::INFINITY, 0);
+        for split in 0..BUCKETS - 1 {
+            let (mut left, mut right) = (Bounds::empty(), Bounds::empty());
+            let (mut n_left, mut n_right) = (0, 0);
+            for b in 0..=split {
+                left = left.merge(&boxes[b]);
+                n_left += counts[b];
+            }
+            for b in split + 1..BUCKETS {
+                right = right.merge(&boxes[b]);
+                n_right += counts[b];
+            }
+            let cost = TRAVERSAL_COST
+                + (n_left as f64 * left.surface_area() + n_right as f64 * right.surface_area())
+                    / bounds.surface_area();
+            if cost < best.0 {
+                best = (cost, split);
+            }
+        }
+
+        // a leaf costs one intersection per shape
+        if best.0 >= n as f64 && n <= MAX_LEAF_SIZE {
+            return self.push_leaf(primitives, bounds);
+        }
+
+        let mut mid = partition(primitives, |p| bucket_of(p) <= best.1);
+        if mid == 0 || mid == n {
+            // the heuristic could not separate the shapes: split them in half
+            primitives.sort_by(|a, b| axis_of(a.centroid, axis).partial_cmp(&axis_of(b.centroid, axis)).unwrap());
+            mid = n / 2;
+        }
+
+        // reserve the slot of this node before building the children,
+        // so the root is always the first node
+        self.nodes.push(BvhNode { bounds, left: 0, right: 0, start: 0, count: 0 });
+        let idx = self.nodes.len() - 1;
+        let (left, right) = primitives.split_at_mut(mid);
+        let left = self.build_node(left);
+        let right = self.build_node(right);
+        self.nodes[idx].left = left;
+        self.nodes[idx].right = right;
+        idx
+    }
+}
+
+/// Return the component of the point along the given axis
+fn axis_of(p: Point, axis: usize) -> f64 {
+    match axis {
+        0 => p.x,
+        1 => p.y,
+        _ => p.z,
+    }
+}
+
+/// This function moves the primitives matching the predicate at the
+/// beginning of the slice and returns how many of them there are
+fn partition<F: Fn(&Primitive) -> bool>(primitives: &mut [Primitive], pred: F) -> usize {
+    let mut mid = 0;
+    for i in 0..primitives.len() {
+        if pred(&primitives[i]) {
+            primitives.swap(i, mid);
+            mid += 1;
+        }
+    }
+    mid
+}
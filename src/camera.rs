@@ -1,9 +1,21 @@
 use crate::canvas::Canvas;
+use crate::color::Color;
 use crate::matrix::{mat4::identity, Mat4};
 use crate::ray::Ray;
+use crate::sampler::{Adaptive, Filter, Sampler};
 use crate::tuple::Point;
 use crate::world::World;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::thread;
 
+/// The camera maps the [hsize x vsize] pixels of the canvas onto the world, with
+/// a field of view of [fov: f64] radians. It renders the rows of the image on
+/// [threads: usize] threads, by default as many as the machine has cores.
+/// Every pixel is sampled [samples: usize] times, placed by the [sampler: Sampler]
+/// and combined by the [filter: Filter]: more than one sample smooths the
+/// jagged edges of the objects. With one sample the ray goes through the center.
+/// With [adaptive: Option<Adaptive>] set, [samples] is ignored: the camera
+/// only adds samples to the pixels on the edges, where the colors change
 #[derive(Debug, Clone, Copy, PartialEq)]
 pub struct Camera {
     pub hsize: usize,
@@ -12,6 +24,11 @@ pub struct Camera {
     pub half_width: f64,
     pub half_height: f64,
     pub pixel_size: f64,
+    pub threads: usize,
+    pub samples: usize,
+    pub sampler: Sampler,
+    pub filter: Filter,
+    pub adaptive: Option<Adaptive>,
     transform: Mat4,
     inverse_transform: Mat4,
 }
@@ -33,6 +50,11 @@ impl Camera {
             half_width,
             half_height,
             pixel_size,
+            threads: thread::available_parallelism().map_or(1, |n| n.get()),
+            samples: 1,
+            sampler: Sampler::Grid,
+            filter: Filter::Box,
+            adaptive: None,
             transform: identity(),
             inverse_transform: identity().inv(),
         }
@@ -50,9 +72,16 @@ impl Camera {
     /// This function return a Ray that start at the camera and
     /// pass to the given [x, y] pixel on the canvas
     pub fn ray_for_pixel(&self, x: usize, y: usize) -> Ray {
-        // the offset from the edge of the canvas to the pixel's center
-        let x_offset = (x as f64 + 0.5) * self.pixel_size;
-        let y_offset = (y as f64 + 0.5) * self.pixel_size;
+        self.ray_for_pixel_offset(x, y, 0.5, 0.5)
+    }
+
+    /// This function return a Ray that start at the camera and pass to the
+    /// given [x, y] pixel on the canvas, at [dx, dy] from its top left corner.
+    /// The offsets are fractions of the pixel: 0.5, 0.5 is its center
+    pub fn ray_for_pixel_offset(&self, x: usize, y: usize, dx: f64, dy: f64) -> Ray {
+        // the offset from the edge of the canvas to the point in the pixel
+        let x_offset = (x as f64 + dx) * self.pixel_size;
+        let y_offset = (y as f64 + dy) * self.pixel_size;
 
         // the untransformed coordinates of the pixel in world space.
         // (remember that the camera looks toward -z, so +x is to the *left*.)
@@ -68,15 +97,140 @@ impl Camera {
         Ray::new(origin, direction)
     }
 
-    /// This function cast a ray through each of world canvas pixels
+    /// This function return the color of the [x, y] pixel, the average of
+    /// its samples weighted by the filter
+    pub fn pixel_color(&self, w: &World, x: usize, y: usize) -> Color {
+        self.sampled_color(w, x, y, self.samples)
+    }
+
+    /// This function return the color of the [x, y] pixel
+    /// sampled [samples: usize] times
+    fn sampled_color(&self, w: &World, x: usize, y: usize, samples: usize) -> Color {
+        if samples <= 1 {
+            return w.color_at(self.ray_for_pixel(x, y));
+        }
+        let mut sum = Color::black();
+        let mut total = 0.0;
+        for (dx, dy) in self.sampler.offsets(samples, x, y) {
+            let weight = self.filter.weight(dx - 0.5, dy - 0.5);
+            sum = sum + w.color_at(self.ray_for_pixel_offset(x, y, dx, dy)) * weight;
+            total += weight;
+        }
+        sum * (1.0 / total)
+    }
+
+    /// This function cast a ray through each of world canvas pixels.
+    /// The threads take the rows of the image one at a time until they are
+    /// all done, each pixel is computed exactly as on a single thread
     pub fn render(&self, w: World) -> Canvas {
+        // the objects might have changed since the world was created
+        let mut w = w;
+        w.build_bvh();
+        let w = &w;
+
+        let samples = if self.adaptive.is_some() { 1 } else { self.samples };
+        let rows = self.in_parallel(self.vsize, |y| {
+            (0..self.hsize)
+                .map(|x| self.sampled_color(w, x, y, samples))
+                .collect::<Vec<Color>>()
+        });
+
         let mut image = Canvas::new(self.hsize, self.vsize);
-        for y in 0..self.vsize {
-            for x in 0..self.hsize {
-                let r = self.ray_for_pixel(x, y);
-                image[(x, y)] = w.color_at(r);
+        for (y, row) in rows.into_iter().enumerate() {
+            for (x, color) in row.into_iter().enumerate() {
+                image[(x, y)] = color;
             }
         }
+
+        if let Some(adaptive) = self.adaptive {
+            self.refine(w, &mut image, adaptive);
+        }
         image
     }
+
+    /// Sample again the pixels of the [image: Canvas] that differ too much
+    /// from their neighbors, 4 times as many samples each round. After the
+    /// first round only the pixels refined in the previous one are checked
+    fn refine(&self, w: &World, image: &mut Canvas, adaptive: Adaptive) {
+        let mut candidates: Vec<(usize, usize)> = (0..self.vsize)
+            .flat_map(|y| (0..self.hsize).map(move |x| (x, y)))
+            .collect();
+        let mut samples = 1;
+        for _ in 0..adaptive.max_depth {
+            let edges: Vec<(usize, usize)> = candidates
+                .into_iter()
+                .filter(|&(x, y)| self.is_edge(image, x, y, adaptive.threshold))
+                .collect();
+            if edges.is_empty() {
+                break;
+            }
+            samples *= 4;
+            let colors = self.in_parallel(edges.len(), |i| {
+                let (x, y) = edges[i];
+                self.sampled_color(w, x, y, samples)
+            });
+            for (&(x, y), color) in edges.iter().zip(colors) {
+                image[(x, y)] = color;
+            }
+            candidates = edges;
+        }
+    }
+
+    /// Return true if the [x, y] pixel differs from one of its four
+    /// neighbors by more than [threshold: f64] in any channel
+    fn is_edge(&self, image: &Canvas, x: usize, y: usize, threshold: f64) -> bool {
+        let c = image[(x, y)];
+        let mut neighbors = vec![];
+        if x > 0 {
+            neighbors.push((x - 1, y));
+        }
+        if x + 1 < self.hsize {
+            neighbors.push((x + 1, y));
+        }
+        if y > 0 {
+            neighbors.push((x, y - 1));
+        }
+        if y + 1 < self.vsize {
+            neighbors.push((x, y + 1));
+        }
+        neighbors.into_iter().any(|n| {
+            let d = image[n] - c;
+            d.r.abs().max(d.g.abs()).max(d.b.abs()) > threshold
+        })
+    }
+
+    /// Run [job] for every index from 0 to [count: usize] on the camera's
+    /// threads, and return the results in order. The threads take the
+    /// indices one at a time until they are all done
+    fn in_parallel<T, F>(&self, count: usize, job: F) -> Vec<T>
+    where
+        T: Send,
+        F: Fn(usize) -> T + Sync,
+    {
+        let next = AtomicUsize::new(0);
+        let threads = self.threads.clamp(1, count.max(1));
+        let mut results: Vec<(usize, T)> = thread::scope(|s| {
+            let workers: Vec<_> = (0..threads)
+                .map(|_| {
+                    s.spawn(|| {
+                        let mut done = vec![];
+                        loop {
+                            let i = next.fetch_add(1, Ordering::Relaxed);
+                            if i >= count {
+                                break;
+                            }
+                            done.push((i, job(i)));
+                        }
+                        done
+                    })
+                })
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().unwrap())
+                .collect()
+        });
+        results.sort_by_key(|(i, _)| *i);
+        results.into_iter().map(|(_, result)| result).collect()
+    }
 }
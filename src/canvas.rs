@@ -1,8 +1,9 @@
-use std::{cmp::min, ops::{Index, IndexMut}};
+use std::{cmp::min, fs, ops::{Index, IndexMut}, path::Path};
 
 
-use crate::color::Color;
-#[derive(Debug, Clone)]
+use crate::color::{Color, Transfer};
+use crate::tonemap::ToneMap;
+#[derive(Debug, Clone, PartialEq)]
 pub struct Canvas {
     pub width: usize,
     pub height: usize,
@@ -28,12 +29,50 @@ impl Canvas {
         (row * self.width) + col
     }
 
+    /// Return the rows of pixels from the top of the image. A canvas
+    /// 0 pixels wide still has [height] rows, all of them empty
+    fn rows(&self) -> impl DoubleEndedIterator<Item = &[Color]> {
+        (0..self.height).map(move |y| &self.pixels[y * self.width..(y + 1) * self.width])
+    }
+
     pub fn backgound(&mut self, color: Color){
         for i in 0..self.pixels.len() {
             self.pixels[i] = color;
         }
     }
 
+    /// Return a copy of the canvas with every pixel passed through
+    /// [tone_map], ready to be saved as an 8-bit image
+    pub fn tone_map(&self, tone_map: &ToneMap) -> Canvas {
+        self.map_pixels(|c| tone_map.apply(c))
+    }
+
+    /// Return a copy of the canvas with only the exposure of [tone_map]
+    /// applied: the colors above 1 are kept, for the [is_hdr_file] formats
+    pub fn expose(&self, tone_map: &ToneMap) -> Canvas {
+        self.map_pixels(|c| tone_map.expose(c))
+    }
+
+    fn map_pixels(&self, f: impl Fn(Color) -> Color) -> Canvas {
+        Canvas {
+            width: self.width,
+            height: self.height,
+            pixels: self.pixels.iter().map(|c| f(*c)).collect(),
+        }
+    }
+
+    /// Return a copy of the canvas with every color encoded by [transfer],
+    /// clamped between 0 and 1, see [Color::encode]
+    pub fn encode(&self, transfer: Transfer) -> Canvas {
+        self.map_pixels(|c| c.encode(transfer))
+    }
+
+    /// Return true if [file_name] is saved in one of the high dynamic range
+    /// formats, which keep the colors above 1 (.hdr, .pfm and .exr)
+    pub fn is_hdr_file(file_name: &str) -> bool {
+        ["hdr", "pfm", "exr"].iter().any(|e| has_extension(file_name, e))
+    }
+
     pub fn to_raw_buffer(&self) -> Vec<u8> {
         let mut out: Vec<u8> = Vec::new();
         for pixel in &self.pixels {
@@ -45,16 +84,326 @@ impl Canvas {
         out
     }
     
+    /// Save the canvas to [file_name], in the format given by its extension.
+    /// PPM files are written as plain text by [to_ppm]. The high dynamic
+    /// range formats keep the colors above 1: Radiance (.hdr) by [to_hdr],
+    /// PFM (.pfm) by [to_pfm] and OpenEXR (.exr) by [to_exr]. The other
+    /// formats (PNG, JPEG, ...) go through the image crate. The colors of
+    /// PPM files are linear, the ones of the image crate formats sRGB
     pub fn save(&self, file_name:&str) -> Result<(), String> {
+        self.save_with(file_name, None)
+    }
+
+    /// Same as [save], with the colors of the 8-bit formats encoded by
+    /// [transfer: Option<Transfer>] instead of the default of the format.
+    /// The high dynamic range formats are always linear
+    pub fn save_with(&self, file_name: &str, transfer: Option<Transfer>) -> Result<(), String> {
+        let extension = Path::new(file_name)
+            .extension()
+            .and_then(|e| e.to_str())
+            .map(|e| e.to_ascii_lowercase());
+        let encoded = match extension.as_deref() {
+            Some("ppm") => Some(self.encode(transfer.unwrap_or(Transfer::Linear)).to_ppm().into_bytes()),
+            Some("hdr") => Some(self.to_hdr()),
+            Some("pfm") => Some(self.to_pfm()),
+            Some("exr") => Some(self.to_exr()),
+            _ => None,
+        };
+        if let Some(bytes) = encoded {
+            return fs::write(file_name, bytes)
+                .map_err(|e| format!("Error saving the canvas {}: {}", file_name, e));
+        }
         match image::save_buffer(
             file_name,
-            &self.to_raw_buffer()[..], 
+            &self.encode(transfer.unwrap_or(Transfer::Srgb)).to_raw_buffer()[..], 
             self.width as u32, self.height as u32, 
             image::ColorType::Rgb8){
                 Ok(_) => Ok(()),
                 Err(_) => Err(String::from("Error saving the canvas"))
             }
     }
+
+    /// Load a canvas from an image file, in any of
+    /// the formats the image crate can read (PNG, JPEG, ...), their
+    /// colors are decoded from sRGB like [save] writes them.
+    /// PPM files are read by [from_ppm]
+    pub fn load(file_name: &str) -> Result<Self, String> {
+        if has_extension(file_name, "ppm") {
+            return match fs::read(file_name) {
+                Ok(data) => Self::from_ppm(&data).map_err(|e| format!("Error loading the image {}: {}", file_name, e)),
+                Err(e) => Err(format!("Error loading the image {}: {}", file_name, e)),
+            };
+        }
+        let img = match image::open(file_name) {
+            Ok(img) => img.to_rgb8(),
+            Err(e) => return Err(format!("Error loading the image {}: {}", file_name, e)),
+        };
+        let mut canvas = Canvas::new(img.width() as usize, img.height() as usize);
+        for (x, y, pixel) in img.enumerate_pixels() {
+            canvas[(x as usize, y as usize)] = Color::from((pixel[0], pixel[1], pixel[2])).decode(Transfer::Srgb);
+        }
+        Ok(canvas)
+    }
+}
+
+impl Canvas {
+    /// Return the canvas as a plain text PPM (P3) image: a header with the
+    /// size and the maximum color value, then the red, green and blue value
+    /// of every pixel, from 0 to 255. Every row of pixels starts on a new
+    /// line and no line is longer than 70 characters
+    pub fn to_ppm(&self) -> String {
+        let mut ppm = format!("P3\n{} {}\n255\n", self.width, self.height);
+        for row in self.rows() {
+            let mut line = String::new();
+            for pixel in row {
+                let (r, g, b) = pixel.to_bytes();
+                for value in [r, g, b] {
+                    let value = value.to_string();
+                    if !line.is_empty() && line.len() + 1 + value.len() > 70 {
+                        ppm.push_str(&line);
+                        ppm.push('\n');
+                        line.clear();
+                    }
+                    if !line.is_empty() {
+                        line.push(' ');
+                    }
+                    line.push_str(&value);
+                }
+            }
+            ppm.push_str(&line);
+            ppm.push('\n');
+        }
+        ppm
+    }
+
+    /// Return the canvas as a binary PPM (P6) image: the same
+    /// header of [to_ppm], then one byte for every color value
+    pub fn to_ppm_binary(&self) -> Vec<u8> {
+        let mut ppm = format!("P6\n{} {}\n255\n", self.width, self.height).into_bytes();
+        ppm.extend(self.to_raw_buffer());
+        ppm
+    }
+
+    /// Read a plain text (P3) or binary (P6) PPM image. Comments are
+    /// skipped, and the values are scaled by the maximum color value
+    /// of the header, so they end up between 0 and 1
+    pub fn from_ppm(data: &[u8]) -> Result<Self, String> {
+        let mut reader = PpmReader { data, pos: 0 };
+        let magic = reader.token()?;
+        if magic != "P3" && magic != "P6" {
+            return Err(format!("unsupported PPM type `{}`, expected P3 or P6", magic));
+        }
+        let width = reader.number("width")?;
+        let height = reader.number("height")?;
+        let maxval = reader.number("maximum color value")?;
+        if width == 0 || height == 0 {
+            return Err(format!("invalid image size {}x{}", width, height));
+        }
+        if maxval == 0 || maxval > 65535 {
+            return Err(format!("invalid maximum color value {}", maxval));
+        }
+
+        // every value takes at least a byte, a bigger image can't fit in the data
+        let count = width
+            .checked_mul(height)
+            .and_then(|pixels| pixels.checked_mul(3))
+            .filter(|&count| count <= data.len())
+            .ok_or_else(|| format!("image size {}x{} is too large for {} bytes of data", width, height, data.len()))?;
+        let values: Vec<usize> = if magic == "P3" {
+            (0..count)
+                .map(|_| reader.number("color value"))
+                .collect::<Result<_, _>>()?
+        } else {
+            // a single whitespace separates the header from the pixels,
+            // which take two bytes each (most significant first) above 255
+            let start = reader.pos + 1;
+            let size = if maxval < 256 { 1 } else { 2 };
+            let len = count.checked_mul(size).ok_or_else(|| format!("image size {}x{} is too large", width, height))?;
+            let raw = start
+                .checked_add(len)
+                .and_then(|end| data.get(start..end))
+                .ok_or_else(|| format!("expected {} bytes of pixels, found {}", len, data.len().saturating_sub(start)))?;
+            raw.chunks(size)
+                .map(|c| c.iter().fold(0, |v, &b| v << 8 | b as usize))
+                .collect()
+        };
+
+        let mut canvas = Canvas::new(width, height);
+        for (pixel, rgb) in canvas.pixels.iter_mut().zip(values.chunks(3)) {
+            let scale = |v: usize| (v.min(maxval) as f64) / maxval as f64;
+            *pixel = Color::new(scale(rgb[0]), scale(rgb[1]), scale(rgb[2]));
+        }
+        Ok(canvas)
+    }
+}
+
+impl Canvas {
+    /// Return the canvas as a Radiance RGBE image (.hdr). Every pixel takes
+    /// 4 bytes: the three colors share the exponent in the last one, so the
+    /// values above 1 survive. The rows are run-length encoded when they are
+    /// between 8 and 32767 pixels wide, as most readers expect
+    pub fn to_hdr(&self) -> Vec<u8> {
+        let header = format!(
+            "#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y {} +X {}\n",
+            self.height, self.width
+        );
+        let mut out = header.into_bytes();
+        for row in self.rows() {
+            let rgbe: Vec<[u8; 4]> = row.iter().map(|c| to_rgbe(*c)).collect();
+            if !(8..=32767).contains(&self.width) {
+                out.extend(rgbe.iter().flatten());
+                continue;
+            }
+            out.extend([2, 2, (self.width >> 8) as u8, (self.width & 0xff) as u8]);
+            // every channel is written on its own, as runs of
+            // up to 128 literal bytes preceded by their count
+            for channel in 0..4 {
+                let values: Vec<u8> = rgbe.iter().map(|p| p[channel]).collect();
+                for chunk in values.chunks(128) {
+                    out.push(chunk.len() as u8);
+                    out.extend(chunk);
+                }
+            }
+        }
+        out
+    }
+
+    /// Return the canvas as a Portable Float Map (.pfm): after a short text
+    /// header every color is a little-endian 32 bits float. The rows are
+    /// stored from the bottom of the image to the top
+    pub fn to_pfm(&self) -> Vec<u8> {
+        // a negative scale says the floats are little-endian
+        let mut out = format!("PF\n{} {}\n-1.0\n", self.width, self.height).into_bytes();
+        for row in self.rows().rev() {
+            for c in row {
+                for v in [c.r, c.g, c.b] {
+                    out.extend((v as f32).to_le_bytes());
+                }
+            }
+        }
+        out
+    }
+
+    /// Return the canvas as an uncompressed OpenEXR image (.exr), with
+    /// the R, G and B channels stored as 32 bits floats
+    pub fn to_exr(&self) -> Vec<u8> {
+        let (w, h) = (self.width as i32, self.height as i32);
+        let mut out = vec![0x76, 0x2f, 0x31, 0x01, 2, 0, 0, 0];
+
+        // the channels must be sorted by name, each is a FLOAT (2),
+        // not perceptually linear, sampled on every pixel
+        let mut channels = vec![];
+        for name in ["B", "G", "R"] {
+            channels.extend(name.as_bytes());
+            channels.push(0);
+            channels.extend(2i32.to_le_bytes());
+            channels.extend([0, 0, 0, 0]);
+            channels.extend(1i32.to_le_bytes());
+            channels.extend(1i32.to_le_bytes());
+        }
+        channels.push(0);
+        let window: Vec<u8> = [0, 0, w - 1, h - 1].iter().flat_map(|v| v.to_le_bytes()).collect();
+        let attributes: [(&str, &str, Vec<u8>); 8] = [
+            ("channels", "chlist", channels),
+            ("compression", "compression", vec![0]),
+            ("dataWindow", "box2i", window.clone()),
+            ("displayWindow", "box2i", window),
+            ("lineOrder", "lineOrder", vec![0]),
+            ("pixelAspectRatio", "float", 1f32.to_le_bytes().to_vec()),
+            ("screenWindowCenter", "v2f", [0f32, 0f32].iter().flat_map(|v| v.to_le_bytes()).collect()),
+            ("screenWindowWidth", "float", 1f32.to_le_bytes().to_vec()),
+        ];
+        for (name, kind, value) in attributes {
+            out.extend(name.as_bytes());
+            out.push(0);
+            out.extend(kind.as_bytes());
+            out.push(0);
+            out.extend((value.len() as i32).to_le_bytes());
+            out.extend(value);
+        }
+        out.push(0);
+
+        // without compression every block is one row: its y, its size
+        // and the values of each channel for the whole row in turn
+        let row_size = self.width * 3 * 4;
+        let table_end = out.len() + self.height * 8;
+        for y in 0..self.height {
+            let offset = table_end + y * (8 + row_size);
+            out.extend((offset as u64).to_le_bytes());
+        }
+        for (y, row) in self.rows().enumerate() {
+            out.extend((y as i32).to_le_bytes());
+            out.extend((row_size as i32).to_le_bytes());
+            for channel in [|c: &Color| c.b, |c: &Color| c.g, |c: &Color| c.r] {
+                for c in row {
+                    out.extend((channel(c) as f32).to_le_bytes());
+                }
+            }
+        }
+        out
+    }
+}
+
+/// Return the color as RGBE: the mantissas of the three channels
+/// and their common exponent. Negative values become 0
+fn to_rgbe(c: Color) -> [u8; 4] {
+    let (r, g, b) = (c.r.max(0.0), c.g.max(0.0), c.b.max(0.0));
+    let v = r.max(g).max(b);
+    if v < 1e-32 {
+        return [0, 0, 0, 0];
+    }
+    // v = m * 2^e with m in [0.5, 1)
+    let mut e = v.log2().floor() as i32 + 1;
+    if v / 2f64.powi(e) >= 1.0 {
+        e += 1;
+    }
+    let e = e.clamp(-128, 127);
+    let scale = 256.0 / 2f64.powi(e);
+    let mantissa = |x: f64| (x * scale).min(255.0) as u8;
+    [mantissa(r), mantissa(g), mantissa(b), (e + 128) as u8]
+}
+
+/// Split the header of a PPM image (and the pixels of a
+/// P3 one) in tokens, skipping whitespace and comments
+struct PpmReader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl PpmReader<'_> {
+    fn token(&mut self) -> Result<String, String> {
+        loop {
+            match self.data.get(self.pos) {
+                Some(b'#') => {
+                    while !matches!(self.data.get(self.pos), Some(b'\n') | None) {
+                        self.pos += 1;
+                    }
+                }
+                Some(c) if c.is_ascii_whitespace() => self.pos += 1,
+                Some(_) => break,
+                None => return Err(String::from("unexpected end of the image")),
+            }
+        }
+        let start = self.pos;
+        while matches!(self.data.get(self.pos), Some(c) if !c.is_ascii_whitespace() && *c != b'#') {
+            self.pos += 1;
+        }
+        Ok(String::from_utf8_lossy(&self.data[start..self.pos]).into_owned())
+    }
+
+    fn number(&mut self, what: &str) -> Result<usize, String> {
+        let token = self.token()?;
+        token
+            .parse::<usize>()
+            .map_err(|_| format!("invalid {} `{}`", what, token))
+    }
+}
+
+/// Return true if [file_name] ends with the given [extension], in any case
+fn has_extension(file_name: &str, extension: &str) -> bool {
+    Path::new(file_name)
+        .extension()
+        .is_some_and(|e| e.eq_ignore_ascii_case(extension))
 }
 
 // https://stackoverflow.com/questions/33770989/implementing-the-index-operator-for-matrices-with-multiple-parameters
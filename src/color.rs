@@ -63,9 +63,25 @@ impl Color {
         }
     }
 
+    /// Return the color as the bytes of an image, as they are: [encode] it
+    /// first for an sRGB image. Values outside 0 and 1 are clamped: to keep
+    /// the highlights tone map the canvas first, see [crate::tonemap::ToneMap]
     pub fn to_bytes(&self) -> (u8, u8, u8) {
         (f64_to_u8(self.r), f64_to_u8(self.g), f64_to_u8(self.b))
     }
+
+    /// Return the linear color encoded by [transfer: Transfer],
+    /// clamped between 0 and 1, ready for [to_bytes]
+    pub fn encode(&self, transfer: Transfer) -> Color {
+        let encode = |c: f64| transfer.encode(c.clamp(0.0, 1.0));
+        Color::new(encode(self.r), encode(self.g), encode(self.b))
+    }
+
+    /// Return the color of an image encoded by [transfer: Transfer] as linear
+    pub fn decode(&self, transfer: Transfer) -> Color {
+        let decode = |c: f64| transfer.decode(c.clamp(0.0, 1.0));
+        Color::new(decode(self.r), decode(self.g), decode(self.b))
+    }
 }
 
 impl PartialEq for Color {
@@ -76,6 +92,54 @@ impl PartialEq for Color {
     }
 }
 
+/// Convert a linear color value to sRGB, the way
+/// monitors and image files expect it, both between 0 and 1
+pub fn linear_to_srgb(c: f64) -> f64 {
+    if c <= 0.0031308 {
+        c * 12.92
+    } else {
+        1.055 * c.powf(1.0 / 2.4) - 0.055
+    }
+}
+
+/// Convert an sRGB color value back to linear, both between 0 and 1
+pub fn srgb_to_linear(c: f64) -> f64 {
+    if c <= 0.04045 {
+        c / 12.92
+    } else {
+        ((c + 0.055) / 1.055).powf(2.4)
+    }
+}
+
+/// How the colors are stored in the bytes of an image. [Linear] keeps
+/// them as they are, like the PPM files of the book. [Srgb] applies the
+/// sRGB transfer function that displays and most image formats (PNG,
+/// JPEG, ...) expect, so the dark tones get more of the 256 levels
+#[derive(Debug, Copy, Clone, PartialEq, Eq, Default)]
+pub enum Transfer {
+    #[default]
+    Linear,
+    Srgb,
+}
+
+impl Transfer {
+    /// Encode the linear value [c: f64], between 0 and 1
+    pub fn encode(&self, c: f64) -> f64 {
+        match self {
+            Transfer::Linear => c,
+            Transfer::Srgb => linear_to_srgb(c),
+        }
+    }
+
+    /// Decode the value [c: f64] of an image back to linear, between 0 and 1
+    pub fn decode(&self, c: f64) -> f64 {
+        match self {
+            Transfer::Linear => c,
+            Transfer::Srgb => srgb_to_linear(c),
+        }
+    }
+}
+
 impl From<(u8, u8, u8)> for Color {
     fn from(color: (u8, u8, u8)) -> Self {
         Self {
@@ -1,6 +1,10 @@
 use crate::shape::Shape;
 use crate::tuple::*;
 
+/// The values precomputed for an intersection that shading needs: where the
+/// hit is, the eye and normal vectors there, the points just above and just
+/// below the surface, and the refractive indices [n1: f64] of the material
+/// the ray is leaving and [n2: f64] of the one it is entering
 pub struct Comps {
     pub t: f64,
     pub object: Box<dyn Shape>,
@@ -9,4 +13,30 @@ pub struct Comps {
     pub normalv: Vector,
     pub inside: bool,
     pub over_point: Point,
+    pub under_point: Point,
+    pub reflectv: Vector,
+    pub n1: f64,
+    pub n2: f64,
+}
+
+impl Comps {
+    /// This function return the reflectance, the fraction of the light that
+    /// is reflected by the surface rather than refracted through it, using
+    /// the Schlick approximation of the Fresnel equations
+    pub fn schlick(&self) -> f64 {
+        // find the cosine of the angle between the eye and normal vectors
+        let mut cos = self.eyev.dot(&self.normalv);
+        // total internal reflection can only occur if n1 > n2
+        if self.n1 > self.n2 {
+            let n = self.n1 / self.n2;
+            let sin2_t = n.powi(2) * (1.0 - cos.powi(2));
+            if sin2_t > 1.0 {
+                return 1.0;
+            }
+            // when n1 > n2 use cos(theta_t) instead
+            cos = (1.0 - sin2_t).sqrt();
+        }
+        let r0 = ((self.n1 - self.n2) / (self.n1 + self.n2)).powi(2);
+        r0 + (1.0 - r0) * (1.0 - cos).powi(5)
+    }
 }
@@ -8,21 +8,42 @@ use crate::EPSILON;
 /// The type contains the value [t: f64] of the intersection
 /// and the [object: <dyn shape>] that was intersect by a ray. The value
 /// of the intersection represent the distance in unit from the origin of the ray
-/// to the point of intersection with the shape
+/// to the point of intersection with the shape. Shapes that have surface
+/// coordinates (like triangles) also record where the hit happened on the
+/// surface in [u: f64] and [v: f64]
 #[derive(Debug, Clone)]
 pub struct Intersection {
     pub t: f64,
     pub object: Box<dyn Shape>,
+    pub u: f64,
+    pub v: f64,
 }
 
 impl Intersection {
     pub fn new(t: f64, object: Box<dyn Shape>) -> Self {
-        Self { t, object }
+        Self { t, object, u: 0.0, v: 0.0 }
     }
 
+    /// Create an intersection that also records the [u, v]
+    /// surface coordinates of the hit
+    pub fn new_with_uv(t: f64, object: Box<dyn Shape>, u: f64, v: f64) -> Self {
+        Self { t, object, u, v }
+    }
+
+    /// Precompute the values needed to shade this intersection. The
+    /// refractive indices are computed as if the ray crossed nothing but
+    /// this hit, use [prepare_computation_with] when shading transparent
+    /// objects that can overlap
     pub fn prepare_computation(&self, r: Ray) -> Comps {
+        self.prepare_computation_with(r, &Intersections::new(vec![self.clone()]))
+    }
+
+    /// Precompute the values needed to shade this intersection, using
+    /// all the intersections of the ray [xs: Intersections] to find which
+    /// object the ray is leaving and which one it is entering
+    pub fn prepare_computation_with(&self, r: Ray, xs: &Intersections) -> Comps {
         let point = r.position(self.t);
-        let mut normalv = self.object.normal_at(point);
+        let mut normalv = self.object.normal_at_hit(point, self);
         let eyev = -r.direction;
         let mut inside = false;
 
@@ -30,15 +51,43 @@ impl Intersection {
             inside = true;
             normalv = -normalv;
         }
+        let (n1, n2) = self.refractive_indices(xs);
         Comps {
             t: self.t,
             object: self.object.clone_box(),
-            point: point,
-            eyev: eyev,
-            normalv: normalv,
-            inside: inside,
+            point,
+            eyev,
+            normalv,
+            inside,
             over_point: point + normalv * EPSILON,
+            under_point: point - normalv * EPSILON,
+            reflectv: r.direction.reflect(normalv),
+            n1,
+            n2,
+        }
+    }
+
+    /// Walk the sorted intersections keeping the list of the objects the
+    /// ray is inside of, and return the refractive index of the material
+    /// the ray is leaving and of the one it is entering at this hit
+    fn refractive_indices(&self, xs: &Intersections) -> (f64, f64) {
+        let mut containers: Vec<&dyn Shape> = vec![];
+        let mut n1 = 1.0;
+        for i in xs.xs.iter() {
+            if i == self {
+                n1 = containers.last().map_or(1.0, |o| o.material().refractive_index);
+            }
+            // the ray either leaves the object or enters it
+            match containers.iter().position(|o| o.id() == i.object.id()) {
+                Some(idx) => { containers.remove(idx); }
+                None => containers.push(i.object.as_ref()),
+            }
+            if i == self {
+                let n2 = containers.last().map_or(1.0, |o| o.material().refractive_index);
+                return (n1, n2);
+            }
         }
+        (n1, 1.0)
     }
 }
 
@@ -26,17 +26,76 @@ pub mod shape {
     pub use shape::Shape;
     pub use sphere::Sphere;
     pub use plane::Plane;
+    pub use cube::Cube;
+    pub use cylinder::Cylinder;
+    pub use cone::Cone;
+    pub use triangle::Triangle;
+    pub use smooth_triangle::SmoothTriangle;
+    pub use group::Group;
+    pub use csg::{Csg, CsgOperation};
+    pub use transform::ShapeTransform;
     pub mod shape;
     pub mod sphere;
     pub mod plane;
+    pub mod cube;
+    pub mod cylinder;
+    pub mod cone;
+    pub mod triangle;
+    pub mod smooth_triangle;
+    pub mod group;
+    pub mod csg;
+    pub mod transform;
 }
 
+pub mod pattern {
+    pub use base::Pattern;
+    pub use stripe::Stripe;
+    pub use gradient::Gradient;
+    pub use ring::Ring;
+    pub use checker::Checker;
+    pub use solid::Solid;
+    pub use blended::Blended;
+    pub use perturbed::Perturbed;
+    pub use texture_map::{CubeMap, TextureMap};
+    pub use uv::{TextureFilter, UvAlignCheck, UvCheckers, UvImage, UvMapping, UvPattern};
+    pub mod base;
+    pub mod stripe;
+    pub mod gradient;
+    pub mod ring;
+    pub mod checker;
+    pub mod solid;
+    pub mod blended;
+    pub mod perturbed;
+    pub mod perlin;
+    pub mod uv;
+    pub mod texture_map;
+}
+
+pub mod bounds;
+pub mod bvh;
 pub mod camera;
 pub mod comps;
 pub mod intersection;
-pub mod light;
+pub mod light {
+    pub use point::Light;
+    pub use area::AreaLight;
+    pub use source::{LightSample, LightSource};
+    pub use attenuation::Attenuation;
+    pub use spot::SpotLight;
+    pub use directional::DirectionalLight;
+    pub mod source;
+    pub mod point;
+    pub mod area;
+    pub mod spot;
+    pub mod directional;
+    pub mod attenuation;
+}
 pub mod material;
+pub mod obj;
 pub mod ray;
+pub mod sampler;
+pub mod scene;
+pub mod tonemap;
 pub mod world;
 
 pub const EPSILON: f64 = 0.00001;
@@ -0,0 +1,120 @@
+use crate::color::Color;
+use crate::sampler::{grid, jitter};
+use crate::tuple::{Point, Vector};
+// `Any` allows us to do dynamic typecasting.
+use std::any::Any;
+use super::attenuation::Attenuation;
+use super::source::{LightSample, LightSource};
+
+
+/// A rectangular light, it starts at [corner: Point] and it is split in
+/// [usteps: usize] cells along the edge [uvec: Vector] (the size of one cell)
+/// and [vsteps: usize] cells along the edge [vvec: Vector]. Every cell is
+/// sampled once, at a point moved around inside the cell when [jitter: bool]
+/// is set, so the shadows it casts get soft edges. Like point lights it has an
+/// [intensity: Color] and can fade with distance through its [attenuation: Attenuation]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct AreaLight {
+    pub corner: Point,
+    pub uvec: Vector,
+    pub usteps: usize,
+    pub vvec: Vector,
+    pub vsteps: usize,
+    pub jitter: bool,
+    pub intensity: Color,
+    pub attenuation: Attenuation,
+}
+
+impl AreaLight {
+    /// Create an area light with a corner at [corner: Point] and the two edges
+    /// [full_uvec: Vector] and [full_vvec: Vector], split in [usteps: usize]
+    /// and [vsteps: usize] cells. The samples are jittered
+    pub fn new(corner: Point, full_uvec: Vector, usteps: usize, full_vvec: Vector, vsteps: usize, intensity: Color) -> Self {
+        let (usteps, vsteps) = (usteps.max(1), vsteps.max(1));
+        Self {
+            corner,
+            uvec: full_uvec / usteps as f64,
+            usteps,
+            vvec: full_vvec / vsteps as f64,
+            vsteps,
+            jitter: true,
+            intensity,
+            attenuation: Attenuation::None,
+        }
+    }
+
+    /// Same as [new], but the light is sampled at [samples: usize] points,
+    /// spread over a grid of cells as close to square as the number allows
+    /// (16 samples are a 4x4 grid, 8 a 2x4 one)
+    pub fn with_samples(corner: Point, full_uvec: Vector, full_vvec: Vector, samples: usize, intensity: Color) -> Self {
+        let (usteps, vsteps) = grid(samples);
+        Self::new(corner, full_uvec, usteps, full_vvec, vsteps, intensity)
+    }
+
+    /// Return the center of the light
+    pub fn position(&self) -> Point {
+        self.corner + self.uvec * (self.usteps as f64 * 0.5) + self.vvec * (self.vsteps as f64 * 0.5)
+    }
+
+    /// Return how many points the light is sampled at, one per cell
+    pub fn sample_count(&self) -> usize {
+        self.usteps * self.vsteps
+    }
+
+    /// Return the point sampled on the cell [u, v] of the light. The jitter
+    /// depends on the [lit: Point] being shaded, so neighboring points see
+    /// different samples, while the same point always sees the same ones
+    pub fn point_on_light(&self, u: usize, v: usize, lit: Point) -> Point {
+        let (ju, jv) = if self.jitter {
+            jitter(&[(u as u64) << 32 | v as u64, lit.x.to_bits(), lit.y.to_bits(), lit.z.to_bits()])
+        } else {
+            (0.5, 0.5)
+        };
+        self.corner + self.uvec * (u as f64 + ju) + self.vvec * (v as f64 + jv)
+    }
+
+    /// Return all the points sampled on the light, one per cell
+    pub fn sample_points(&self, lit: Point) -> Vec<Point> {
+        let mut points = Vec::with_capacity(self.sample_count());
+        for v in 0..self.vsteps {
+            for u in 0..self.usteps {
+                points.push(self.point_on_light(u, v, lit));
+            }
+        }
+        points
+    }
+}
+
+impl LightSource for AreaLight {
+    fn intensity(&self) -> Color {
+        self.intensity
+    }
+
+    fn samples(&self, lit: Point) -> Vec<LightSample> {
+        self.sample_points(lit)
+            .into_iter()
+            .map(|position| LightSample::toward(position, lit, self.intensity))
+            .collect()
+    }
+
+    fn attenuation(&self) -> Attenuation {
+        self.attenuation
+    }
+
+    fn clone_box(&self) -> Box<dyn LightSource> {
+        Box::new(*self)
+    }
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        match other.downcast_ref::<Self>() {
+            Some(l) => self == l,
+            _ => false
+        }
+    }
+}
@@ -0,0 +1,46 @@
+/// How the light of a source fades with the distance from it:
+/// [None] keeps the same brightness at any distance, [Linear] fades
+/// it out evenly until it is gone at the given range, and
+/// [InverseSquare] makes it fall off like real light does, with the
+/// radius of the source keeping it finite right next to the light
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Attenuation {
+    #[default]
+    None,
+    Linear(f64),
+    InverseSquare(f64),
+}
+
+impl Attenuation {
+    /// Return a [Linear] attenuation, the light is gone past [range: f64]
+    pub fn linear(range: f64) -> Result<Self, String> {
+        if range > 0.0 && range.is_finite() {
+            Ok(Attenuation::Linear(range))
+        } else {
+            Err(format!("the range of a linear attenuation must be a positive number, found {}", range))
+        }
+    }
+
+    /// Return an [InverseSquare] attenuation for a source of radius [radius: f64]
+    pub fn inverse_square(radius: f64) -> Result<Self, String> {
+        if radius > 0.0 && radius.is_finite() {
+            Ok(Attenuation::InverseSquare(radius))
+        } else {
+            Err(format!("the radius of an inverse square attenuation must be a positive number, found {}", radius))
+        }
+    }
+
+    /// Return the fraction of the light left at [distance: f64] from the source,
+    /// 1 right at the source. Lights infinitely far away only keep their
+    /// light when they don't attenuate
+    pub fn factor(&self, distance: f64) -> f64 {
+        match *self {
+            Attenuation::None => 1.0,
+            Attenuation::Linear(range) => (1.0 - distance / range).clamp(0.0, 1.0),
+            Attenuation::InverseSquare(radius) => {
+                let d = distance / radius + 1.0;
+                1.0 / (d * d)
+            }
+        }
+    }
+}
@@ -0,0 +1,58 @@
+use crate::color::Color;
+use crate::tuple::{Point, Vector};
+
+// `Any` allows us to do dynamic typecasting.
+use std::any::Any;
+use super::source::{LightSample, LightSource};
+
+
+/// A light so far away that all its rays are parallel, like the sun. It
+/// only has the [direction: Vector] its rays travel in and an
+/// [intensity: Color]: it lights every point the same way, and anything
+/// between the point and the sky, however far, casts a shadow on it
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct DirectionalLight {
+    pub direction: Vector,
+    pub intensity: Color,
+}
+
+impl DirectionalLight {
+    pub fn new(direction: Vector, intensity: Color) -> Self {
+        Self {
+            direction: direction.normalize(),
+            intensity,
+        }
+    }
+}
+
+impl LightSource for DirectionalLight {
+    fn intensity(&self) -> Color {
+        self.intensity
+    }
+
+    fn samples(&self, _lit: Point) -> Vec<LightSample> {
+        // the light comes from the opposite of the way its rays travel
+        vec![LightSample {
+            direction: -self.direction,
+            distance: f64::INFINITY,
+            intensity: self.intensity,
+        }]
+    }
+
+    fn clone_box(&self) -> Box<dyn LightSource> {
+        Box::new(*self)
+    }
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        match other.downcast_ref::<Self>() {
+            Some(l) => self == l,
+            _ => false
+        }
+    }
+}
@@ -0,0 +1,61 @@
+use crate::color::Color;
+use crate::tuple::Point;
+// `Any` allows us to do dynamic typecasting.
+use std::any::Any;
+use super::attenuation::Attenuation;
+use super::source::{LightSample, LightSource};
+
+
+/// A point light: a light source with no size existing at single
+/// [position: Point] in space. It is also defined by its [intensity: Color]
+/// which describes how bright is the color of the light source. By default
+/// the light is as bright at any distance, its [attenuation: Attenuation]
+/// can make it fade away from the light
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Light {
+    pub position: Point,
+    pub intensity: Color,
+    pub attenuation: Attenuation,
+}
+
+
+impl Light {
+    pub fn new(position: Point, intensity: Color) -> Self {
+        Self {
+            position,
+            intensity,
+            attenuation: Attenuation::None,
+        }
+    }
+}
+
+impl LightSource for Light {
+    fn intensity(&self) -> Color {
+        self.intensity
+    }
+
+    fn samples(&self, lit: Point) -> Vec<LightSample> {
+        vec![LightSample::toward(self.position, lit, self.intensity)]
+    }
+
+    fn attenuation(&self) -> Attenuation {
+        self.attenuation
+    }
+
+    fn clone_box(&self) -> Box<dyn LightSource> {
+        Box::new(*self)
+    }
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        match other.downcast_ref::<Self>() {
+            Some(l) => self == l,
+            _ => false
+        }
+    }
+}
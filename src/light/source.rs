@@ -0,0 +1,79 @@
+use crate::color::Color;
+use crate::tuple::{Point, Vector};
+use std::fmt;
+// `Any` allows us to do dynamic typecasting.
+use std::any::Any;
+use super::attenuation::Attenuation;
+
+
+/// One of the points a light is sampled at, as seen from the point being lit:
+/// the [direction: Vector] toward the light (normalized), the [distance: f64]
+/// to it, infinite when the light is infinitely far away, and the
+/// [intensity: Color] reaching the lit point from there
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct LightSample {
+    pub direction: Vector,
+    pub distance: f64,
+    pub intensity: Color,
+}
+
+impl LightSample {
+    /// Create the sample of a light at [position: Point] seen from [lit: Point]
+    pub fn toward(position: Point, lit: Point, intensity: Color) -> Self {
+        let v = position - lit;
+        Self {
+            direction: v.normalize(),
+            distance: v.magnitude(),
+            intensity,
+        }
+    }
+}
+
+/// What every kind of light has in common. Shading only needs to know
+/// from which directions the light reaches a point, how far away it is
+/// in each of them (for the shadow rays) and with which intensity
+pub trait LightSource : fmt::Debug + Send + Sync {
+    /// Return the color and brightness of the light
+    fn intensity(&self) -> Color;
+
+    /// Return the points the light is sampled at, as seen from [lit: Point].
+    /// Lights with no size have a single sample
+    fn samples(&self, lit: Point) -> Vec<LightSample>;
+
+    /// Return how the light fades with distance. By default it doesn't
+    fn attenuation(&self) -> Attenuation {
+        Attenuation::None
+    }
+
+    /// Return the [samples] with their intensity faded by the [attenuation].
+    /// Shading a point takes them once, then uses the same ones to test
+    /// the shadows and to compute the diffuse and specular light
+    fn attenuated_samples(&self, lit: Point) -> Vec<LightSample> {
+        let attenuation = self.attenuation();
+        self.samples(lit)
+            .into_iter()
+            .map(|s| LightSample { intensity: s.intensity * attenuation.factor(s.distance), ..s })
+            .collect()
+    }
+
+    // I need this because can't implement a clone for a trait object
+    fn clone_box(&self) -> Box<dyn LightSource>;
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any;
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool;
+}
+
+impl Clone for Box<dyn LightSource> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+impl PartialEq for Box<dyn LightSource> {
+    fn eq(&self, other: &Box<dyn LightSource>) -> bool {
+        self.eq_box(other.as_any())
+    }
+}
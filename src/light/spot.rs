@@ -0,0 +1,85 @@
+use crate::color::Color;
+use crate::tuple::{Point, Vector};
+
+// `Any` allows us to do dynamic typecasting.
+use std::any::Any;
+use super::attenuation::Attenuation;
+use super::source::{LightSample, LightSource};
+
+
+/// A light at [position: Point] that only shines inside a cone around its
+/// [direction: Vector], like a desk lamp or a stage spot. Inside the
+/// [inner_angle: f64] the light has its full [intensity: Color], past the
+/// [outer_angle: f64] it is dark, and in between it fades out smoothly.
+/// Both angles are measured from the direction, in radians. Like
+/// point lights it can fade with distance through its [attenuation: Attenuation]
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct SpotLight {
+    pub position: Point,
+    pub direction: Vector,
+    pub inner_angle: f64,
+    pub outer_angle: f64,
+    pub intensity: Color,
+    pub attenuation: Attenuation,
+}
+
+impl SpotLight {
+    pub fn new(position: Point, direction: Vector, inner_angle: f64, outer_angle: f64, intensity: Color) -> Self {
+        Self {
+            position,
+            direction: direction.normalize(),
+            inner_angle,
+            outer_angle,
+            intensity,
+            attenuation: Attenuation::None,
+        }
+    }
+
+    /// Return how much of the light reaches [lit: Point], between 0
+    /// outside the outer cone and 1 inside the inner cone
+    pub fn falloff(&self, lit: Point) -> f64 {
+        let cos = self.direction.dot(&(lit - self.position).normalize());
+        let cos_inner = self.inner_angle.cos();
+        let cos_outer = self.outer_angle.cos();
+        if cos >= cos_inner {
+            return 1.0;
+        }
+        if cos <= cos_outer {
+            return 0.0;
+        }
+        // smoothstep between the two cones
+        let t = (cos - cos_outer) / (cos_inner - cos_outer);
+        t * t * (3.0 - 2.0 * t)
+    }
+}
+
+impl LightSource for SpotLight {
+    fn intensity(&self) -> Color {
+        self.intensity
+    }
+
+    fn samples(&self, lit: Point) -> Vec<LightSample> {
+        vec![LightSample::toward(self.position, lit, self.intensity * self.falloff(lit))]
+    }
+
+    fn attenuation(&self) -> Attenuation {
+        self.attenuation
+    }
+
+    fn clone_box(&self) -> Box<dyn LightSource> {
+        Box::new(*self)
+    }
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        match other.downcast_ref::<Self>() {
+            Some(l) => self == l,
+            _ => false
+        }
+    }
+}
@@ -11,14 +11,37 @@ use raytracer::matrix::mat4::view_transform;
 use raytracer::matrix::mat4::{identity, rotate_z, scale};
 use raytracer::matrix::Mat4;
 use raytracer::ray::Ray;
+use raytracer::scene::Scene;
 use raytracer::shape::Plane;
 use raytracer::shape::Shape;
 use raytracer::shape::Sphere;
+use raytracer::tonemap::{ToneMap, ToneOperator};
 use raytracer::tuple::*;
 use raytracer::world::World;
 use std::f64::consts::PI;
+use std::path::Path;
+use std::process::ExitCode;
+use std::time::Instant;
+use std::{env, fs};
+
+const USAGE: &str = "\
+Usage: raytracer [OPTIONS] [SCENE]
+
+Render the YAML scene file SCENE, or one of the built-in demos.
+
+Options:
+  -d, --demo NAME          render the built-in demo NAME instead of a scene file
+  -l, --list               list the built-in demos
+  -o, --output PATH        where to save the image (default: render/<name>.png)
+  -r, --resolution WxH     override the size of the camera, like 1920x1080
+  -s, --samples N          samples per pixel
+  -t, --threads N          rendering threads (default: one per core)
+  -e, --exposure STOPS     brighten (or darken, if negative) the image by STOPS
+      --tone OPERATOR      how to fit the brightest colors in the image: clamp
+                           (default), reinhard, extended-reinhard:WHITE or aces.
+                           Not for .hdr, .pfm and .exr, which keep them all
+  -h, --help               print this help";
 
-//use std::time;
 #[derive(Debug)]
 struct Projectile {
     position: Point,
@@ -37,7 +60,7 @@ fn tick(env: &Environment, proj: Projectile) -> Projectile {
     }
 }
 
-fn projectile() {
+fn projectile() -> Canvas {
     let mut p = Projectile {
         position: Point::new(0.0, 1.0, 0.0),
         velocity: Vector::new(1.0, 1.8, 0.0).normalize() * 11.65,
@@ -55,10 +78,10 @@ fn projectile() {
         cv[(p.position.x as usize, (550 - p.position.y as usize))] = RED;
     }
 
-    cv.save("./render/projectile.png").unwrap();
+    cv
 }
 
-fn clock() {
+fn clock() -> Canvas {
     // Chapter 04 challenge:
     // Write a program that uses a rotation matrix to cmpute the position of those hours nt the clock face,
     // and draw a pixel onto a canvas for each of them.
@@ -90,10 +113,10 @@ fn clock() {
         canvas[(position.x as usize, height - position.y as usize)] = RED;
     }
 
-    canvas.save("./render/clock.png").unwrap();
+    canvas
 }
 
-fn move_a_point() {
+fn move_a_point() -> Canvas {
     let mut cv = Canvas::new(200, 200);
 
     let start_point = Point::new(100.0, 100.0, 0.0);
@@ -109,10 +132,10 @@ fn move_a_point() {
         cv[(position.x as usize, 200usize - position.y as usize)] = RED;
         position = position + velocity;
     }
-    cv.save("./render/move_a_point.png").unwrap();
+    cv
 }
 
-fn raycast_2d_sphere() {
+fn raycast_2d_sphere() -> Canvas {
     // Chapter 05 Challenge:
     // casts rays at a sphere and draws the picture to a canvas.
     let canvas_pixels = 100 as usize;
@@ -156,10 +179,10 @@ fn raycast_2d_sphere() {
             }
         }
     }
-    canvas.save("./render/2d_red_sphere.png").unwrap();
+    canvas
 }
 
-fn raycast_3d_sphere() {
+fn raycast_3d_sphere() -> Canvas {
     // Chapter 06 Challenge:
     // casts rays at a sphere and draws the picture to a canvas.
     let canvas_pixels = 1000 as usize;
@@ -200,15 +223,15 @@ fn raycast_3d_sphere() {
                 canvas[(x, y)] = hit
                     .object
                     .material()
-                    .lighting(light, point, eye, normal, false);
+                    .lighting(hit.object.as_ref(), &light, point, eye, normal, 1.0);
                 //println!("{} elpased.", start.elapsed().as_micros());
             }
         }
     }
-    canvas.save("./render/3d_red_sphere.png").unwrap();
+    canvas
 }
 
-fn ch7() {
+fn ch7() -> Scene {
     // FLOOR
     let t = scale(10.0, 0.01, 10.0);
     let mtr = Material::new(Some(Color::new(1.0, 0.9, 0.9)), None, None, Some(0.0), None);
@@ -270,7 +293,7 @@ fn ch7() {
     ));
     // THE WORLD
     let world = World::new(
-        Some(Light::new(Point::new(-10., 10.0, -10.0), WHITE)),
+        Some(vec![Box::new(Light::new(Point::new(-10., 10.0, -10.0), WHITE))]),
         Some(vec![floor, left_wall, right_wall, middle, right, left]),
     );
     // THE CAMERA
@@ -280,12 +303,10 @@ fn ch7() {
         Point::new(0., 1., 0.),
         Vector::new(0., 1., 0.),
     ));
-    //THE CANVAS
-    let canvas = camera.render(world);
-    canvas.save("./render/ch7.png").unwrap();
+    Scene { camera, world }
 }
 
-fn ch9() {
+fn ch9() -> Scene {
     // FLOOR
     let mtr = Material::new(Some(GREEN), Some(0.2), None, Some(1.0), Some(1.0));
     let floor = Box::new(Plane::new(None, Some(mtr)));
@@ -322,7 +343,7 @@ fn ch9() {
     ));
     // THE WORLD
     let world = World::new(
-        Some(Light::new(Point::new(-10., 10.0, -10.0), WHITE)),
+        Some(vec![Box::new(Light::new(Point::new(-10., 10.0, -10.0), WHITE))]),
         Some(vec![floor, middle, right, left]),
     );
     // THE CAMERA
@@ -332,17 +353,204 @@ fn ch9() {
         Point::new(0., 1., 0.),
         Vector::new(0., 1., 0.),
     ));
-    //THE CANVAS
-    let canvas = camera.render(world);
-    canvas.save("./render/ch9.png").unwrap();
+    Scene { camera, world }
 }
 
-fn main() {
-    //move_a_point();
-    //projectile();
-    //clock();
-    //raycast_2d_sphere();
-    //raycast_3d_sphere();
-    //ch7();
-    //ch9();
+/// A built-in demo: the early chapters draw straight on
+/// a canvas, the later ones build a scene for the camera
+enum Demo {
+    Canvas(fn() -> Canvas),
+    Scene(fn() -> Scene),
+}
+
+const DEMOS: [(&str, &str, Demo); 7] = [
+    ("projectile", "chapter 2, the trajectory of a projectile", Demo::Canvas(projectile)),
+    ("clock", "chapter 4, the hours on a clock face", Demo::Canvas(clock)),
+    ("move-a-point", "a point moving along a line", Demo::Canvas(move_a_point)),
+    ("2d-sphere", "chapter 5, the silhouette of a sphere", Demo::Canvas(raycast_2d_sphere)),
+    ("3d-sphere", "chapter 6, a shaded sphere", Demo::Canvas(raycast_3d_sphere)),
+    ("ch7", "chapter 7, three spheres in a room", Demo::Scene(ch7)),
+    ("ch9", "chapter 9, three spheres on a plane", Demo::Scene(ch9)),
+];
+
+/// What the command line asked for
+#[derive(Debug, Default)]
+struct Options {
+    scene: Option<String>,
+    demo: Option<String>,
+    output: Option<String>,
+    resolution: Option<(usize, usize)>,
+    samples: Option<usize>,
+    threads: Option<usize>,
+    exposure: Option<f64>,
+    tone: Option<ToneOperator>,
+    help: bool,
+    list: bool,
+}
+
+fn parse_args(args: &[String]) -> Result<Options, String> {
+    let mut options = Options::default();
+    let mut args = args.iter();
+    while let Some(arg) = args.next() {
+        let mut value = || {
+            args.next()
+                .cloned()
+                .ok_or_else(|| format!("`{}` needs a value", arg))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => options.help = true,
+            "-l" | "--list" => options.list = true,
+            "-d" | "--demo" => options.demo = Some(value()?),
+            "-o" | "--output" => options.output = Some(value()?),
+            "-r" | "--resolution" => options.resolution = Some(parse_resolution(&value()?)?),
+            "-s" | "--samples" => options.samples = Some(parse_count(arg, &value()?)?),
+            "-t" | "--threads" => options.threads = Some(parse_count(arg, &value()?)?),
+            "-e" | "--exposure" => options.exposure = Some(parse_exposure(&value()?)?),
+            "--tone" => options.tone = Some(ToneOperator::parse(&value()?)?),
+            _ if arg.starts_with('-') => return Err(format!("unknown option `{}`", arg)),
+            _ if options.scene.is_some() => return Err(format!("unexpected argument `{}`", arg)),
+            _ => options.scene = Some(arg.clone()),
+        }
+    }
+    if options.scene.is_some() && options.demo.is_some() {
+        return Err(String::from("give either a scene file or a demo, not both"));
+    }
+    if options.tone.is_some() && options.output.as_deref().is_some_and(Canvas::is_hdr_file) {
+        return Err(String::from(
+            "--tone only applies to 8-bit images, .hdr, .pfm and .exr files keep the colors above 1"));
+    }
+    Ok(options)
+}
+
+/// Parse a resolution like 1920x1080
+fn parse_resolution(value: &str) -> Result<(usize, usize), String> {
+    let size = value
+        .split_once('x')
+        .and_then(|(w, h)| Some((w.parse::<usize>().ok()?, h.parse::<usize>().ok()?)));
+    match size {
+        Some((w, h)) if w > 0 && h > 0 => Ok((w, h)),
+        _ => Err(format!("invalid resolution `{}`, expected something like 1920x1080", value)),
+    }
+}
+
+/// Parse a number of samples or threads, at least 1
+fn parse_count(option: &str, value: &str) -> Result<usize, String> {
+    match value.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(format!("`{}` needs a positive number, found `{}`", option, value)),
+    }
+}
+
+/// Parse an exposure in stops, like 1.5 or -2
+fn parse_exposure(value: &str) -> Result<f64, String> {
+    match value.parse::<f64>() {
+        Ok(stops) if stops.is_finite() => Ok(stops),
+        _ => Err(format!("invalid exposure `{}`, expected a number of stops like 1.5", value)),
+    }
+}
+
+/// Return a copy of the [camera] with a different size,
+/// looking at the world in the same way
+fn resize(camera: &Camera, hsize: usize, vsize: usize) -> Camera {
+    let mut resized = Camera::new(hsize, vsize, camera.fov);
+    resized.set_transform(camera.transform());
+    resized.threads = camera.threads;
+    resized.samples = camera.samples;
+    resized.sampler = camera.sampler;
+    resized.filter = camera.filter;
+    resized.adaptive = camera.adaptive;
+    resized
+}
+
+fn run(options: Options) -> Result<(), String> {
+    if options.help {
+        println!("{}", USAGE);
+        return Ok(());
+    }
+    if options.list {
+        for (name, description, _) in DEMOS.iter() {
+            println!("{:<14} {}", name, description);
+        }
+        return Ok(());
+    }
+
+    let start = Instant::now();
+    let (name, scene) = match (&options.scene, &options.demo) {
+        (Some(path), _) => {
+            let name = Path::new(path)
+                .file_stem()
+                .map_or(String::from("scene"), |s| s.to_string_lossy().into_owned());
+            (name, Scene::from_file(path)?)
+        }
+        (None, Some(demo)) => match DEMOS.iter().find(|(name, _, _)| name == demo) {
+            Some((name, _, Demo::Scene(build))) => (name.to_string(), build()),
+            Some((name, _, Demo::Canvas(draw))) => return draw_demo(name, *draw, &options),
+            None => return Err(format!("unknown demo `{}`, see the list with --list", demo)),
+        },
+        (None, None) => return Err(format!("nothing to render\n\n{}", USAGE)),
+    };
+    let built = start.elapsed();
+
+    let mut camera = scene.camera;
+    if let Some((w, h)) = options.resolution {
+        camera = resize(&camera, w, h);
+    }
+    camera.samples = options.samples.unwrap_or(camera.samples);
+    camera.threads = options.threads.unwrap_or(camera.threads);
+    let canvas = camera.render(scene.world);
+    println!(
+        "Rendered {} at {}x{}, {} samples per pixel on {} threads in {:.2?} (scene built in {:.2?})",
+        name, camera.hsize, camera.vsize, camera.samples, camera.threads, start.elapsed() - built, built
+    );
+    save(&canvas, &name, &options)
+}
+
+/// Draw one of the demos that don't use the camera
+fn draw_demo(name: &str, draw: fn() -> Canvas, options: &Options) -> Result<(), String> {
+    if options.resolution.is_some() || options.samples.is_some() || options.threads.is_some() {
+        return Err(format!(
+            "the demo `{}` is drawn directly on a canvas, --resolution, --samples \
+             and --threads only apply to scenes rendered by the camera", name));
+    }
+    let start = Instant::now();
+    let canvas = draw();
+    println!("Drew {} at {}x{} in {:.2?}", name, canvas.width, canvas.height, start.elapsed());
+    save(&canvas, name, options)
+}
+
+/// Save the [canvas] at the output of the [options], or in render/ after the
+/// [name] of what it shows. The canvas is tone mapped first if the options
+/// ask for it. The HDR formats only get the exposure, they keep the colors above 1
+fn save(canvas: &Canvas, name: &str, options: &Options) -> Result<(), String> {
+    let output = options.output.clone().unwrap_or(format!("render/{}.png", name));
+    let tone_mapped;
+    let canvas = if options.exposure.is_some() || options.tone.is_some() {
+        let tone_map = ToneMap::new(options.exposure.unwrap_or(0.0), options.tone.unwrap_or_default());
+        tone_mapped = if Canvas::is_hdr_file(&output) {
+            canvas.expose(&tone_map)
+        } else {
+            canvas.tone_map(&tone_map)
+        };
+        &tone_mapped
+    } else {
+        canvas
+    };
+    if let Some(dir) = Path::new(&output).parent().filter(|d| !d.as_os_str().is_empty()) {
+        fs::create_dir_all(dir)
+            .map_err(|e| format!("Error creating the directory {}: {}", dir.display(), e))?;
+    }
+    canvas.save(&output)?;
+    println!("Saved {}", output);
+    Ok(())
+}
+
+fn main() -> ExitCode {
+    let args: Vec<String> = env::args().skip(1).collect();
+    match parse_args(&args).and_then(run) {
+        Ok(()) => ExitCode::SUCCESS,
+        Err(e) => {
+            eprintln!("error: {}", e);
+            ExitCode::FAILURE
+        }
+    }
 }
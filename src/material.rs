@@ -1,6 +1,9 @@
 use crate::color::Color; 
-use crate::light::Light;
+use crate::light::{LightSample, LightSource};
+use crate::pattern::Pattern;
+use crate::shape::Shape;
 use crate::tuple::*;
+use std::sync::Arc;
 
 
 /// This struct encapsulates the material surface [color: Color]
@@ -12,14 +15,26 @@ use crate::tuple::*;
 /// [specular: f64] reflection - is the reflection of the light source itself alsso called specular light. 
 /// Is the bright spot on a curved surface. It dependes only on the angle between the reflecion vector and
 /// the eye vector and is controlled by a parameter that we'll call [shiness: f64]. The higher the shiness.
-/// the smaller and tighter the specular light
-#[derive(Debug, Clone, Copy, PartialEq)]
+/// the smaller and tighter the specular light.
+/// Besides the Phong attributes, [reflective: f64] says how much of the
+/// scene the surface mirrors: 0 is not reflective at all, 1 is a perfect mirror.
+/// [transparency: f64] says how much light passes through the surface, and
+/// [refractive_index: f64] how much that light bends (1 for vacuum and air,
+/// 1.333 for water, 1.52 for glass, 2.417 for diamond).
+/// When the material has a [pattern: Option<Arc<dyn Pattern>>] the pattern
+/// decides the color of each point of the surface, and [color] is ignored.
+/// The pattern is shared, so cloning a material doesn't copy it
+#[derive(Debug, Clone)]
 pub struct Material {
     pub color: Color,
     pub ambient: f64,
     pub diffuse: f64,
     pub specular: f64,
     pub shininess: f64,
+    pub reflective: f64,
+    pub transparency: f64,
+    pub refractive_index: f64,
+    pub pattern: Option<Arc<dyn Pattern>>,
 }
 
 impl Material {
@@ -31,55 +46,95 @@ impl Material {
             diffuse: diffuse.unwrap_or(0.9),
             specular: specular.unwrap_or(0.9),
             shininess: shininess.unwrap_or(200.0),
+            reflective: 0.0,
+            transparency: 0.0,
+            refractive_index: 1.0,
+            pattern: None,
         }
     }
 
+    /// Return the color of the surface of [object] at the given [point: Point]
+    /// in world space: the pattern's color if there is one, [color] otherwise
+    pub fn color_at(&self, object: &dyn Shape, point: Point) -> Color {
+        match &self.pattern {
+            Some(pattern) => pattern.pattern_at_shape(object, point),
+            None => self.color,
+        }
+    }
+
+    /// Return the ambient contribution of the surface of [object] at [point],
+    /// lit by the [ambient_light: Color] that bounces around the scene. It
+    /// is all that is left of the surface when no light reaches it
+    pub fn ambient_at(&self, object: &dyn Shape, point: Point, ambient_light: Color) -> Color {
+        self.color_at(object, point) * ambient_light * self.ambient
+    }
+
     /// This function is what will shade the [Material] and make the shape three-dimensional. It expects 
-    /// 4 arguments beside itself, the [light: Light] source, the [point: Point] being illuminated, the [eye: Vector]
-    /// and the [normal: Vector] from the Phong reflection models  
-    pub fn lighting(&self, light: Light, point: Point, eye: Vector, normal: Vector, in_shadow: bool) -> Color {
-        // combine the surface color with the light's color/intesity
-        let effective_color = self.color * light.intensity;
-        
-        // find the direction to the light source
-        let lightv = (light.position - point).normalize();
-        
-        // compute the ambient contribution
-        let ambient = effective_color * self.ambient;
+    /// 5 arguments beside itself, the [object: &dyn Shape] being shaded, the [light: &dyn LightSource], the
+    /// [point: Point] being illuminated, the [eye: Vector] and the [normal: Vector] from the Phong reflection models.
+    /// The object is needed to find where the point falls on the material's pattern.
+    /// The last argument [intensity: f64] is the fraction of the light that reaches the point:
+    /// 0 when it is in shadow, 1 when it is fully lit, in between in the penumbra of an area light.
+    /// The ambient contribution is lit by the light itself, a scene with more
+    /// lights adds it once and the [direct_lighting] of each light, see [World](crate::world::World)
+    pub fn lighting(&self, object: &dyn Shape, light: &dyn LightSource, point: Point, eye: Vector, normal: Vector, intensity: f64) -> Color {
+        let samples = light.attenuated_samples(point);
+        self.ambient_at(object, point, light.intensity())
+            + self.direct_lighting(object, &samples, point, eye, normal, intensity)
+    }
 
-        if in_shadow == true {
-            return ambient;
+    /// The diffuse and specular contributions of [lighting] without the ambient one,
+    /// for the given [samples: &[LightSample]] of a light, already faded with the
+    /// distance (see [LightSource::attenuated_samples]). The world passes the same
+    /// samples it tested the shadows with, so both agree on where the light is
+    pub fn direct_lighting(&self, object: &dyn Shape, samples: &[LightSample], point: Point, eye: Vector, normal: Vector, intensity: f64) -> Color {
+        if intensity == 0.0 || samples.is_empty() {
+            return Color::black();
         }
-        
-        let specular: Color;
-        let diffuse: Color;
-        // light_dot_normal represents the cosine ot the angle between the
-        // light vector and the normal vector. A negative number means
-        // the light is on the other side of the surface
-        let light_dot_normal = lightv.dot(&normal);
-        
-        if light_dot_normal < 0.0 {
-            specular= Color::black();
-            diffuse = Color::black();
-        } else  {
+        let color = self.color_at(object, point);
+
+        // average the diffuse and specular contributions
+        // of all the points sampled on the light
+        let mut sum = Color::black();
+        for sample in samples {
+            // the direction to the light source
+            let lightv = sample.direction;
+            let light_intensity = sample.intensity;
+            let effective_color = color * light_intensity;
+
+            // light_dot_normal represents the cosine ot the angle between the
+            // light vector and the normal vector. A negative number means
+            // the light is on the other side of the surface
+            let light_dot_normal = lightv.dot(&normal);
+            if light_dot_normal < 0.0 {
+                continue;
+            }
             // compute the diffuse contribution
-            diffuse = effective_color * self.diffuse * light_dot_normal;
+            sum = sum + effective_color * self.diffuse * light_dot_normal;
 
             // reflect_dot_eye represents the cosine of the angle between the 
             // reflection vector and the eye vector. A negative number meand the 
             // light reflects away from the eye
             let reflectv = (-lightv).reflect(normal);
             let reflect_dot_eye = reflectv.dot(&eye);
-            if reflect_dot_eye <= 0.0 {
-                specular = Color::black();
-            } else {
+            if reflect_dot_eye > 0.0 {
                 // compute the specular contribution
                 let factor = reflect_dot_eye.powf(self.shininess);
-                specular = light.intensity * self.specular * factor;
-                //println!("{:?}", specular);
+                sum = sum + light_intensity * self.specular * factor;
             }
-        } 
-        ambient + diffuse + specular
+        }
+        sum * (intensity / samples.len() as f64)
+    }
+}
+
+impl Material {
+    /// Return a transparent material with the refractive index of glass
+    pub fn glass() -> Self {
+        Self {
+            transparency: 1.0,
+            refractive_index: 1.5,
+            ..Self::default()
+        }
     }
 }
 
@@ -91,6 +146,29 @@ impl Default for Material {
             diffuse: 0.9,
             specular: 0.9,
             shininess: 200.0,
+            reflective: 0.0,
+            transparency: 0.0,
+            refractive_index: 1.0,
+            pattern: None,
         }        
     }
-}
\ No newline at end of file
+}
+
+impl PartialEq for Material {
+    fn eq(&self, other: &Material) -> bool {
+        let same_pattern = match (&self.pattern, &other.pattern) {
+            (Some(a), Some(b)) => a.eq_box(b.as_any()),
+            (None, None) => true,
+            _ => false,
+        };
+        self.color == other.color
+            && self.ambient == other.ambient
+            && self.diffuse == other.diffuse
+            && self.specular == other.specular
+            && self.shininess == other.shininess
+            && self.reflective == other.reflective
+            && self.transparency == other.transparency
+            && self.refractive_index == other.refractive_index
+            && same_pattern
+    }
+}
@@ -0,0 +1,224 @@
+use std::fs;
+
+use crate::shape::{Group, Shape, SmoothTriangle, Triangle};
+use crate::tuple::{Point, Vector};
+
+/// A line of the OBJ file that the parser could not use. It keeps the
+/// 1-based [line: usize] number in the file, the original [content: String]
+/// and a [reason: String] explaining why it was skipped
+#[derive(Debug, Clone, PartialEq)]
+pub struct IgnoredLine {
+    pub line: usize,
+    pub content: String,
+    pub reason: String,
+}
+
+/// The result of parsing a Wavefront OBJ file. It collects the [vertices],
+/// vertex [normals] and [texture_coords] (stored 0-based, while the file
+/// references them 1-based), the triangles of the faces found before any
+/// group statement in [default_group] and the ones that follow a `g name`
+/// (or `o name`) statement in [groups], in the order they appear in the file.
+/// Every line that could not be used ends up in [ignored]
+#[derive(Debug, Clone, Default)]
+pub struct ObjParser {
+    pub vertices: Vec<Point>,
+    pub normals: Vec<Vector>,
+    pub texture_coords: Vec<(f64, f64)>,
+    pub default_group: Vec<Box<dyn Shape>>,
+    pub groups: Vec<(String, Vec<Box<dyn Shape>>)>,
+    pub ignored: Vec<IgnoredLine>,
+}
+
+/// One vertex of a face: the index of its position and, if
+/// the face provides them, the index of its normal
+#[derive(Debug, Clone, Copy)]
+struct FaceVertex {
+    vertex: usize,
+    normal: Option<usize>,
+}
+
+impl ObjParser {
+    /// Read and parse the OBJ file at the given [path]. It only fails
+    /// if the file can't be read, malformed lines are reported in [ignored]
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => Ok(parse_obj(&content)),
+            Err(e) => Err(format!("Error reading the OBJ file {}: {}", path, e)),
+        }
+    }
+
+    /// Return the group with the given [name], if the file declared it
+    pub fn group(&self, name: &str) -> Option<&Vec<Box<dyn Shape>>> {
+        self.groups.iter().find(|(n, _)| n == name).map(|(_, shapes)| shapes)
+    }
+
+    /// Consume the parser and return all the triangles it found,
+    /// ready to be pushed in the world objects
+    pub fn into_shapes(self) -> Vec<Box<dyn Shape>> {
+        let mut out = self.default_group;
+        for (_, shapes) in self.groups {
+            out.extend(shapes);
+        }
+        out
+    }
+
+    /// Consume the parser and return a [Group] containing the triangles
+    /// of the default group, plus one child group for each named group
+    pub fn into_group(self) -> Group {
+        let mut group = Group::new(None);
+        group.add_children(self.default_group);
+        for (_, shapes) in self.groups {
+            let mut child = Group::new(None);
+            child.add_children(shapes);
+            group.add_child(Box::new(child));
+        }
+        group
+    }
+
+    /// Add the triangles to the group currently open, or
+    /// to the default group if none has been declared yet
+    fn push_triangles(&mut self, current_group: Option<usize>, triangles: Vec<Box<dyn Shape>>) {
+        match current_group {
+            Some(idx) => self.groups[idx].1.extend(triangles),
+            None => self.default_group.extend(triangles),
+        }
+    }
+
+    /// Return the index of the group with the given [name],
+    /// creating it if this is the first time it is seen
+    fn open_group(&mut self, name: &str) -> usize {
+        match self.groups.iter().position(|(n, _)| n == name) {
+            Some(idx) => idx,
+            None => {
+                self.groups.push((name.to_string(), vec![]));
+                self.groups.len() - 1
+            }
+        }
+    }
+
+    /// This function converts an OBJ index (1-based, or negative to count
+    /// back from the last element read so far) into a 0-based index
+    fn resolve_index(token: &str, len: usize, what: &str) -> Result<usize, String> {
+        let idx = token
+            .parse::<i64>()
+            .map_err(|_| format!("invalid {} index `{}`", what, token))?;
+        let resolved = if idx > 0 {
+            idx - 1
+        } else if idx < 0 {
+            len as i64 + idx
+        } else {
+            return Err(format!("{} index can't be 0", what));
+        };
+        if resolved < 0 || resolved >= len as i64 {
+            return Err(format!("{} index {} is out of range", what, idx));
+        }
+        Ok(resolved as usize)
+    }
+
+    /// Parse one `v`, `v/vt`, `v//vn` or `v/vt/vn` element of a face
+    fn parse_face_vertex(&self, token: &str) -> Result<FaceVertex, String> {
+        let parts = token.split('/').collect::<Vec<&str>>();
+        if parts.len() > 3 {
+            return Err(format!("invalid face element `{}`", token));
+        }
+        let vertex = Self::resolve_index(parts[0], self.vertices.len(), "vertex")?;
+        if parts.len() > 1 && !parts[1].is_empty() {
+            Self::resolve_index(parts[1], self.texture_coords.len(), "texture")?;
+        }
+        let normal = match parts.get(2) {
+            Some(n) if !n.is_empty() => Some(Self::resolve_index(n, self.normals.len(), "normal")?),
+            _ => None,
+        };
+        Ok(FaceVertex { vertex, normal })
+    }
+
+    /// Turn a face in a list of triangles using a fan triangulation: every
+    /// triangle shares the first vertex of the face. If all the vertices
+    /// carry a normal the triangles are smooth
+    fn triangulate(&self, face: &[FaceVertex]) -> Vec<Box<dyn Shape>> {
+        let mut triangles: Vec<Box<dyn Shape>> = vec![];
+        for i in 1..face.len() - 1 {
+            let (a, b, c) = (face[0], face[i], face[i + 1]);
+            let (p1, p2, p3) = (self.vertices[a.vertex], self.vertices[b.vertex], self.vertices[c.vertex]);
+            match (a.normal, b.normal, c.normal) {
+                (Some(n1), Some(n2), Some(n3)) => triangles.push(Box::new(SmoothTriangle::new(
+                    p1,
+                    p2,
+                    p3,
+                    self.normals[n1],
+                    self.normals[n2],
+                    self.normals[n3],
+                ))),
+                _ => triangles.push(Box::new(Triangle::new(p1, p2, p3))),
+            }
+        }
+        triangles
+    }
+}
+
+/// This function parses the arguments of a statement as
+/// floats, expecting between [min] and [max] of them
+fn parse_floats(args: &[&str], min: usize, max: usize) -> Result<Vec<f64>, String> {
+    if args.len() < min || args.len() > max {
+        return Err(format!("expected {} to {} numbers, found {}", min, max, args.len()));
+    }
+    args.iter()
+        .map(|a| a.parse::<f64>().map_err(|_| format!("invalid number `{}`", a)))
+        .collect()
+}
+
+/// This function parses the content of a Wavefront OBJ file. It understands
+/// vertices (`v`), vertex normals (`vn`), texture coordinates (`vt`), faces
+/// (`f`, triangulated when they have more than three vertices) and named
+/// groups (`g` and `o`). Comments and blank lines are skipped, anything
+/// else is recorded with its line number in [ObjParser::ignored]
+pub fn parse_obj(content: &str) -> ObjParser {
+    let mut parser = ObjParser::default();
+    let mut current_group: Option<usize> = None;
+
+    for (idx, line) in content.lines().enumerate() {
+        let trimmed = line.trim();
+        if trimmed.is_empty() || trimmed.starts_with('#') {
+            continue;
+        }
+        let tokens = trimmed.split_whitespace().collect::<Vec<&str>>();
+        let (keyword, args) = (tokens[0], &tokens[1..]);
+
+        let result = match keyword {
+            "v" => parse_floats(args, 3, 4).map(|v| parser.vertices.push(Point::new(v[0], v[1], v[2]))),
+            "vn" => parse_floats(args, 3, 3).map(|n| parser.normals.push(Vector::new(n[0], n[1], n[2]))),
+            "vt" => parse_floats(args, 1, 3).map(|t| parser.texture_coords.push((t[0], *t.get(1).unwrap_or(&0.0)))),
+            "f" => {
+                if args.len() < 3 {
+                    Err(format!("a face needs at least 3 vertices, found {}", args.len()))
+                } else {
+                    args.iter()
+                        .map(|a| parser.parse_face_vertex(a))
+                        .collect::<Result<Vec<FaceVertex>, String>>()
+                        .map(|face| {
+                            let triangles = parser.triangulate(&face);
+                            parser.push_triangles(current_group, triangles);
+                        })
+                }
+            }
+            "g" | "o" => {
+                if args.is_empty() {
+                    Err(String::from("missing group name"))
+                } else {
+                    current_group = Some(parser.open_group(&args.join(" ")));
+                    Ok(())
+                }
+            }
+            _ => Err(format!("unsupported statement `{}`", keyword)),
+        };
+
+        if let Err(reason) = result {
+            parser.ignored.push(IgnoredLine {
+                line: idx + 1,
+                content: line.to_string(),
+                reason,
+            });
+        }
+    }
+    parser
+}
@@ -0,0 +1,61 @@
+use crate::color::Color;
+use crate::matrix::Mat4;
+use crate::shape::Shape;
+use crate::tuple::Point;
+use std::fmt;
+// `Any` allows us to do dynamic typecasting.
+use std::any::Any;
+
+
+/// A pattern decides the color of a surface point by point. Like a shape,
+/// every pattern has its own [transform: Mat4] and lives in its own pattern
+/// space: a pattern only has to say which color it has at a point in pattern
+/// space ([local_pattern_at]), the default methods of the trait take care of
+/// moving the point from world space to object space to pattern space
+pub trait Pattern : fmt::Debug + Send + Sync {
+    /// Return the color of the pattern at the given point,
+    /// expressed in pattern space
+    fn local_pattern_at(&self, p: Point) -> Color;
+
+    /// Return the color of the pattern at the given point, expressed in the
+    /// space the pattern is applied to (the object, or an outer pattern)
+    fn pattern_at(&self, p: Point) -> Color {
+        self.local_pattern_at(self.inverse_transform() * p)
+    }
+
+    /// Return the color of the pattern on the given [object] at
+    /// the given point, expressed in world space
+    fn pattern_at_shape(&self, object: &dyn Shape, world_point: Point) -> Color {
+        self.pattern_at(object.world_to_object(world_point))
+    }
+
+    /// Return Transform
+    fn transform(&self) -> Mat4;
+
+    /// Return the inverse of the transform
+    fn inverse_transform(&self) -> Mat4;
+
+    /// Set pattern's transform
+    fn set_transform(&mut self, t: Mat4);
+
+    // I need this because can't implement a clone for a trait object
+    fn clone_box(&self) -> Box<dyn Pattern>;
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any;
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool;
+}
+
+impl Clone for Box<dyn Pattern> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+impl PartialEq for Box<dyn Pattern> {
+    fn eq(&self, other: &Box<dyn Pattern>) -> bool {
+        self.eq_box(other.as_any())
+    }
+}
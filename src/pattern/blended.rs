@@ -0,0 +1,75 @@
+use crate::color::Color;
+use crate::matrix::Mat4;
+use crate::tuple::Point;
+
+// `Any` allows us to do dynamic typecasting.
+use std::any::Any;
+use super::base::Pattern;
+
+
+/// A pattern that averages the colors of the two patterns [a: Box<dyn Pattern>]
+/// and [b: Box<dyn Pattern>] at every point. Blending two perpendicular
+/// stripe patterns, for example, gives a plaid
+#[derive(Debug, Clone)]
+pub struct Blended {
+    pub a: Box<dyn Pattern>,
+    pub b: Box<dyn Pattern>,
+    transform: Mat4,
+    inverse_transform: Mat4,
+}
+
+impl Blended {
+    pub fn new(a: Box<dyn Pattern>, b: Box<dyn Pattern>, transform: Option<Mat4>) -> Self {
+        let t = transform.unwrap_or(Mat4::identity());
+        Self {
+            a,
+            b,
+            transform: t,
+            inverse_transform: t.inv(),
+        }
+    }
+}
+
+impl PartialEq for Blended {
+
+    fn eq(&self, other: &Self) -> bool {
+        self.a.eq_box(other.a.as_any()) && self.b.eq_box(other.b.as_any()) && self.transform == other.transform
+    }
+}
+
+impl Pattern for Blended {
+
+    fn local_pattern_at(&self, p: Point) -> Color {
+        (self.a.pattern_at(p) + self.b.pattern_at(p)) * 0.5
+    }
+
+    fn transform(&self) -> Mat4 {
+        self.transform
+    }
+
+    fn inverse_transform(&self) -> Mat4 {
+        self.inverse_transform
+    }
+
+    fn set_transform(&mut self, t: Mat4) {
+        self.transform = t;
+        self.inverse_transform = t.inv();
+    }
+
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        match other.downcast_ref::<Self>() {
+            Some(p) => self == p,
+            _ => false
+        }
+    }
+}
@@ -0,0 +1,88 @@
+use crate::color::Color;
+use crate::matrix::Mat4;
+use crate::tuple::Point;
+
+// `Any` allows us to do dynamic typecasting.
+use std::any::Any;
+use super::base::Pattern;
+use super::solid::Solid;
+
+
+/// A pattern of alternating [a] and [b] unit cubes, like a 3D
+/// checkerboard. On a plane it looks like a regular checkerboard.
+/// [a: Box<dyn Pattern>] and [b: Box<dyn Pattern>] are usually plain colors,
+/// but can be any pattern: they are evaluated in the space of this pattern
+#[derive(Debug, Clone)]
+pub struct Checker {
+    pub a: Box<dyn Pattern>,
+    pub b: Box<dyn Pattern>,
+    transform: Mat4,
+    inverse_transform: Mat4,
+}
+
+impl Checker {
+    pub fn new(a: Color, b: Color, transform: Option<Mat4>) -> Self {
+        Self::nested(Box::new(Solid::new(a)), Box::new(Solid::new(b)), transform)
+    }
+
+    /// Create checkers made of the patterns [a] and [b]
+    pub fn nested(a: Box<dyn Pattern>, b: Box<dyn Pattern>, transform: Option<Mat4>) -> Self {
+        let t = transform.unwrap_or(Mat4::identity());
+        Self {
+            a,
+            b,
+            transform: t,
+            inverse_transform: t.inv(),
+        }
+    }
+}
+
+impl PartialEq for Checker {
+
+    fn eq(&self, other: &Self) -> bool {
+        self.a.eq_box(other.a.as_any()) && self.b.eq_box(other.b.as_any()) && self.transform == other.transform
+    }
+}
+
+impl Pattern for Checker {
+
+    fn local_pattern_at(&self, p: Point) -> Color {
+        // the cubes change every time any coordinate crosses an integer
+        let sum = p.x.floor() + p.y.floor() + p.z.floor();
+        if sum.rem_euclid(2.0) == 0.0 {
+            self.a.pattern_at(p)
+        } else {
+            self.b.pattern_at(p)
+        }
+    }
+
+    fn transform(&self) -> Mat4 {
+        self.transform
+    }
+
+    fn inverse_transform(&self) -> Mat4 {
+        self.inverse_transform
+    }
+
+    fn set_transform(&mut self, t: Mat4) {
+        self.transform = t;
+        self.inverse_transform = t.inv();
+    }
+
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        match other.downcast_ref::<Self>() {
+            Some(p) => self == p,
+            _ => false
+        }
+    }
+}
@@ -0,0 +1,84 @@
+use crate::color::Color;
+use crate::matrix::Mat4;
+use crate::tuple::{Point, Vector};
+
+// `Any` allows us to do dynamic typecasting.
+use std::any::Any;
+use super::base::Pattern;
+use super::perlin::noise;
+
+
+/// A pattern that jitters the point before looking it up in the inner
+/// [pattern: Box<dyn Pattern>], using 3D Perlin noise. The jitter is at most
+/// [scale: f64] along each axis: small values make stripes look like wood
+/// grain or marble veins, larger ones break them into blotches
+#[derive(Debug, Clone)]
+pub struct Perturbed {
+    pub pattern: Box<dyn Pattern>,
+    pub scale: f64,
+    transform: Mat4,
+    inverse_transform: Mat4,
+}
+
+impl Perturbed {
+    pub fn new(pattern: Box<dyn Pattern>, scale: f64, transform: Option<Mat4>) -> Self {
+        let t = transform.unwrap_or(Mat4::identity());
+        Self {
+            pattern,
+            scale,
+            transform: t,
+            inverse_transform: t.inv(),
+        }
+    }
+}
+
+impl PartialEq for Perturbed {
+
+    fn eq(&self, other: &Self) -> bool {
+        self.pattern.eq_box(other.pattern.as_any()) && self.scale == other.scale && self.transform == other.transform
+    }
+}
+
+impl Pattern for Perturbed {
+
+    fn local_pattern_at(&self, p: Point) -> Color {
+        // sample the noise at three far apart places, so
+        // each axis gets its own independent jitter
+        let jitter = Vector::new(
+            noise(p.x, p.y, p.z),
+            noise(p.x + 31.7, p.y + 47.3, p.z + 11.9),
+            noise(p.x + 73.1, p.y + 19.5, p.z + 59.3),
+        );
+        self.pattern.pattern_at(p + jitter * self.scale)
+    }
+
+    fn transform(&self) -> Mat4 {
+        self.transform
+    }
+
+    fn inverse_transform(&self) -> Mat4 {
+        self.inverse_transform
+    }
+
+    fn set_transform(&mut self, t: Mat4) {
+        self.transform = t;
+        self.inverse_transform = t.inv();
+    }
+
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        match other.downcast_ref::<Self>() {
+            Some(p) => self == p,
+            _ => false
+        }
+    }
+}
@@ -0,0 +1,69 @@
+use crate::color::Color;
+use crate::matrix::Mat4;
+use crate::tuple::Point;
+
+// `Any` allows us to do dynamic typecasting.
+use std::any::Any;
+use super::base::Pattern;
+
+
+/// A pattern that has the same [color: Color] everywhere. It is what the
+/// other patterns are made of when they are given plain colors
+#[derive(Debug, Clone, PartialEq)]
+pub struct Solid {
+    pub color: Color,
+    transform: Mat4,
+    inverse_transform: Mat4,
+}
+
+impl Solid {
+    pub fn new(color: Color) -> Self {
+        Self {
+            color,
+            transform: Mat4::identity(),
+            inverse_transform: Mat4::identity(),
+        }
+    }
+}
+
+impl Pattern for Solid {
+
+    fn local_pattern_at(&self, _p: Point) -> Color {
+        self.color
+    }
+
+    fn pattern_at(&self, _p: Point) -> Color {
+        // no need to transform the point, the color is the same everywhere
+        self.color
+    }
+
+    fn transform(&self) -> Mat4 {
+        self.transform
+    }
+
+    fn inverse_transform(&self) -> Mat4 {
+        self.inverse_transform
+    }
+
+    fn set_transform(&mut self, t: Mat4) {
+        self.transform = t;
+        self.inverse_transform = t.inv();
+    }
+
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        match other.downcast_ref::<Self>() {
+            Some(p) => self == p,
+            _ => false
+        }
+    }
+}
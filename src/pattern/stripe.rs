@@ -0,0 +1,86 @@
+use crate::color::Color;
+use crate::matrix::Mat4;
+use crate::tuple::Point;
+
+// `Any` allows us to do dynamic typecasting.
+use std::any::Any;
+use super::base::Pattern;
+use super::solid::Solid;
+
+
+/// A pattern that alternates [a] and [b] in stripes one unit wide along the x axis.
+/// [a: Box<dyn Pattern>] and [b: Box<dyn Pattern>] are usually plain colors,
+/// but can be any pattern: they are evaluated in the space of this pattern
+#[derive(Debug, Clone)]
+pub struct Stripe {
+    pub a: Box<dyn Pattern>,
+    pub b: Box<dyn Pattern>,
+    transform: Mat4,
+    inverse_transform: Mat4,
+}
+
+impl Stripe {
+    pub fn new(a: Color, b: Color, transform: Option<Mat4>) -> Self {
+        Self::nested(Box::new(Solid::new(a)), Box::new(Solid::new(b)), transform)
+    }
+
+    /// Create stripes made of the patterns [a] and [b]
+    pub fn nested(a: Box<dyn Pattern>, b: Box<dyn Pattern>, transform: Option<Mat4>) -> Self {
+        let t = transform.unwrap_or(Mat4::identity());
+        Self {
+            a,
+            b,
+            transform: t,
+            inverse_transform: t.inv(),
+        }
+    }
+}
+
+impl PartialEq for Stripe {
+
+    fn eq(&self, other: &Self) -> bool {
+        self.a.eq_box(other.a.as_any()) && self.b.eq_box(other.b.as_any()) && self.transform == other.transform
+    }
+}
+
+impl Pattern for Stripe {
+
+    fn local_pattern_at(&self, p: Point) -> Color {
+        // the stripes change every time x crosses an integer
+        if p.x.floor().rem_euclid(2.0) == 0.0 {
+            self.a.pattern_at(p)
+        } else {
+            self.b.pattern_at(p)
+        }
+    }
+
+    fn transform(&self) -> Mat4 {
+        self.transform
+    }
+
+    fn inverse_transform(&self) -> Mat4 {
+        self.inverse_transform
+    }
+
+    fn set_transform(&mut self, t: Mat4) {
+        self.transform = t;
+        self.inverse_transform = t.inv();
+    }
+
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        match other.downcast_ref::<Self>() {
+            Some(p) => self == p,
+            _ => false
+        }
+    }
+}
@@ -0,0 +1,168 @@
+use crate::color::Color;
+use crate::matrix::Mat4;
+use crate::tuple::Point;
+
+// `Any` allows us to do dynamic typecasting.
+use std::any::Any;
+use super::base::Pattern;
+use super::uv::{cube_map, CubeFace, UvMapping, UvPattern};
+
+
+/// A pattern that wraps the 2D [uv_pattern: Box<dyn UvPattern>] on the
+/// surface of an object, using the given [mapping: UvMapping] to find
+/// the [u, v] of every point. The mapping should match the shape: spherical
+/// for spheres, planar for planes, cylindrical for cylinders
+#[derive(Debug, Clone)]
+pub struct TextureMap {
+    pub uv_pattern: Box<dyn UvPattern>,
+    pub mapping: UvMapping,
+    transform: Mat4,
+    inverse_transform: Mat4,
+}
+
+impl TextureMap {
+    pub fn new(uv_pattern: Box<dyn UvPattern>, mapping: UvMapping, transform: Option<Mat4>) -> Self {
+        let t = transform.unwrap_or(Mat4::identity());
+        Self {
+            uv_pattern,
+            mapping,
+            transform: t,
+            inverse_transform: t.inv(),
+        }
+    }
+}
+
+impl PartialEq for TextureMap {
+
+    fn eq(&self, other: &Self) -> bool {
+        self.uv_pattern.eq_box(other.uv_pattern.as_any()) && self.mapping == other.mapping && self.transform == other.transform
+    }
+}
+
+impl Pattern for TextureMap {
+
+    fn local_pattern_at(&self, p: Point) -> Color {
+        let (u, v) = self.mapping.map(p);
+        self.uv_pattern.uv_pattern_at(u, v)
+    }
+
+    fn transform(&self) -> Mat4 {
+        self.transform
+    }
+
+    fn inverse_transform(&self) -> Mat4 {
+        self.inverse_transform
+    }
+
+    fn set_transform(&mut self, t: Mat4) {
+        self.transform = t;
+        self.inverse_transform = t.inv();
+    }
+
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        match other.downcast_ref::<Self>() {
+            Some(p) => self == p,
+            _ => false
+        }
+    }
+}
+
+/// A pattern for cubes that gives every face its own 2D pattern, like
+/// a skybox or a labeled box. The faces are seen from outside the cube
+#[derive(Debug, Clone)]
+pub struct CubeMap {
+    pub left: Box<dyn UvPattern>,
+    pub front: Box<dyn UvPattern>,
+    pub right: Box<dyn UvPattern>,
+    pub back: Box<dyn UvPattern>,
+    pub up: Box<dyn UvPattern>,
+    pub down: Box<dyn UvPattern>,
+    transform: Mat4,
+    inverse_transform: Mat4,
+}
+
+impl CubeMap {
+    pub fn new(left: Box<dyn UvPattern>, front: Box<dyn UvPattern>, right: Box<dyn UvPattern>,
+        back: Box<dyn UvPattern>, up: Box<dyn UvPattern>, down: Box<dyn UvPattern>, transform: Option<Mat4>) -> Self {
+        let t = transform.unwrap_or(Mat4::identity());
+        Self {
+            left,
+            front,
+            right,
+            back,
+            up,
+            down,
+            transform: t,
+            inverse_transform: t.inv(),
+        }
+    }
+
+    /// Return the pattern of the given [face]
+    pub fn face(&self, face: CubeFace) -> &dyn UvPattern {
+        match face {
+            CubeFace::Left => self.left.as_ref(),
+            CubeFace::Front => self.front.as_ref(),
+            CubeFace::Right => self.right.as_ref(),
+            CubeFace::Back => self.back.as_ref(),
+            CubeFace::Up => self.up.as_ref(),
+            CubeFace::Down => self.down.as_ref(),
+        }
+    }
+}
+
+impl PartialEq for CubeMap {
+
+    fn eq(&self, other: &Self) -> bool {
+        self.left.eq_box(other.left.as_any()) && self.front.eq_box(other.front.as_any()) && self.right.eq_box(other.right.as_any())
+            && self.back.eq_box(other.back.as_any()) && self.up.eq_box(other.up.as_any()) && self.down.eq_box(other.down.as_any())
+            && self.transform == other.transform
+    }
+}
+
+impl Pattern for CubeMap {
+
+    fn local_pattern_at(&self, p: Point) -> Color {
+        let (face, (u, v)) = cube_map(p);
+        self.face(face).uv_pattern_at(u, v)
+    }
+
+    fn transform(&self) -> Mat4 {
+        self.transform
+    }
+
+    fn inverse_transform(&self) -> Mat4 {
+        self.inverse_transform
+    }
+
+    fn set_transform(&mut self, t: Mat4) {
+        self.transform = t;
+        self.inverse_transform = t.inv();
+    }
+
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        match other.downcast_ref::<Self>() {
+            Some(p) => self == p,
+            _ => false
+        }
+    }
+}
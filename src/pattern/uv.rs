@@ -0,0 +1,328 @@
+use crate::canvas::Canvas;
+use crate::color::Color;
+use crate::tuple::Point;
+use std::f64::consts::PI;
+use std::fmt;
+use std::sync::Arc;
+// `Any` allows us to do dynamic typecasting.
+use std::any::Any;
+
+
+/// The ways a point on the surface of an object can be turned into the
+/// [u, v] coordinates of a 2D texture, both going from 0 to 1
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum UvMapping {
+    /// Longitude and latitude on the unit sphere, like a globe
+    Spherical,
+    /// The x and z coordinates, the texture repeats every unit on the xz plane
+    Planar,
+    /// The angle around the y axis and the height, the texture
+    /// wraps once around the cylinder and repeats every unit along y
+    Cylindrical,
+    /// The position on the face of the -1..+1 cube the point lies on,
+    /// every face gets the whole texture
+    Cube,
+}
+
+impl UvMapping {
+    /// Return the [u, v] coordinates of the given point, in object space
+    pub fn map(&self, p: Point) -> (f64, f64) {
+        match self {
+            UvMapping::Spherical => spherical_map(p),
+            UvMapping::Planar => planar_map(p),
+            UvMapping::Cylindrical => cylindrical_map(p),
+            UvMapping::Cube => cube_map(p).1,
+        }
+    }
+}
+
+/// This function maps a point on the unit sphere to [u, v]: u goes
+/// around the y axis, v from the south pole (0) to the north pole (1)
+pub fn spherical_map(p: Point) -> (f64, f64) {
+    // compute the azimuthal angle, -PI < theta <= PI
+    // it increases clockwise when viewed from above
+    let theta = p.x.atan2(p.z);
+    // the distance from the origin, 1 on the unit sphere
+    let radius = (p - Point::new(0.0, 0.0, 0.0)).magnitude();
+    // compute the polar angle, 0 <= phi <= PI
+    let phi = (p.y / radius).acos();
+    // -0.5 < raw_u <= 0.5, subtract it from 1 so
+    // that u increases counterclockwise from above
+    let raw_u = theta / (2.0 * PI);
+    let u = 1.0 - (raw_u + 0.5);
+    // flip phi so that v is 0 at the south pole
+    let v = 1.0 - phi / PI;
+    (u, v)
+}
+
+/// This function maps a point on the xz plane to [u, v],
+/// repeating every unit in both directions
+pub fn planar_map(p: Point) -> (f64, f64) {
+    (p.x.rem_euclid(1.0), p.z.rem_euclid(1.0))
+}
+
+/// This function maps a point on the unit cylinder to [u, v]: u goes
+/// around the y axis like [spherical_map], v repeats every unit along y
+pub fn cylindrical_map(p: Point) -> (f64, f64) {
+    let theta = p.x.atan2(p.z);
+    let raw_u = theta / (2.0 * PI);
+    let u = 1.0 - (raw_u + 0.5);
+    (u, p.y.rem_euclid(1.0))
+}
+
+/// The six faces of the -1..+1 cube
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CubeFace {
+    Left,
+    Right,
+    Front,
+    Back,
+    Up,
+    Down,
+}
+
+impl CubeFace {
+    /// Return the face of the cube the given point lies on:
+    /// the one matching its largest coordinate
+    pub fn of(p: Point) -> Self {
+        let coord = p.x.abs().max(p.y.abs()).max(p.z.abs());
+        if coord == p.x {
+            CubeFace::Right
+        } else if coord == -p.x {
+            CubeFace::Left
+        } else if coord == p.y {
+            CubeFace::Up
+        } else if coord == -p.y {
+            CubeFace::Down
+        } else if coord == p.z {
+            CubeFace::Front
+        } else {
+            CubeFace::Back
+        }
+    }
+}
+
+/// This function maps a point on the -1..+1 cube to the face it lies on
+/// and the [u, v] coordinates on that face. Seen from outside the cube,
+/// u grows to the right and v upward (for the up and down faces the top
+/// is the back and the front of the cube)
+pub fn cube_map(p: Point) -> (CubeFace, (f64, f64)) {
+    let face = CubeFace::of(p);
+    // shift -1..+1 to 0..2, the remainder keeps points off the
+    // cube (or exactly on its edge) in range, then halve it
+    let uv = |a: f64, b: f64| (a.rem_euclid(2.0) / 2.0, b.rem_euclid(2.0) / 2.0);
+    let uv = match face {
+        CubeFace::Front => uv(p.x + 1.0, p.y + 1.0),
+        CubeFace::Back => uv(1.0 - p.x, p.y + 1.0),
+        CubeFace::Left => uv(p.z + 1.0, p.y + 1.0),
+        CubeFace::Right => uv(1.0 - p.z, p.y + 1.0),
+        CubeFace::Up => uv(p.x + 1.0, 1.0 - p.z),
+        CubeFace::Down => uv(p.x + 1.0, p.z + 1.0),
+    };
+    (face, uv)
+}
+
+/// A 2D pattern, that gives a color to every [u, v] point of a texture.
+/// It is wrapped on the surface of the objects by a
+/// [TextureMap](super::TextureMap) or a [CubeMap](super::CubeMap)
+pub trait UvPattern : fmt::Debug + Send + Sync {
+    /// Return the color at the given [u, v], both between 0 and 1
+    fn uv_pattern_at(&self, u: f64, v: f64) -> Color;
+
+    // I need this because can't implement a clone for a trait object
+    fn clone_box(&self) -> Box<dyn UvPattern>;
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any;
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool;
+}
+
+impl Clone for Box<dyn UvPattern> {
+    fn clone(&self) -> Self {
+        self.clone_box()
+    }
+}
+
+impl PartialEq for Box<dyn UvPattern> {
+    fn eq(&self, other: &Box<dyn UvPattern>) -> bool {
+        self.eq_box(other.as_any())
+    }
+}
+
+/// A checkerboard of [width: usize] squares along u and [height: usize]
+/// squares along v, alternating [a: Color] and [b: Color]
+#[derive(Debug, Clone, PartialEq)]
+pub struct UvCheckers {
+    pub width: usize,
+    pub height: usize,
+    pub a: Color,
+    pub b: Color,
+}
+
+impl UvCheckers {
+    pub fn new(width: usize, height: usize, a: Color, b: Color) -> Self {
+        Self { width, height, a, b }
+    }
+}
+
+impl UvPattern for UvCheckers {
+    fn uv_pattern_at(&self, u: f64, v: f64) -> Color {
+        let u2 = (u * self.width as f64).floor();
+        let v2 = (v * self.height as f64).floor();
+        if (u2 + v2).rem_euclid(2.0) == 0.0 {
+            self.a
+        } else {
+            self.b
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn UvPattern> {
+        Box::new(self.clone())
+    }
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        match other.downcast_ref::<Self>() {
+            Some(p) => self == p,
+            _ => false
+        }
+    }
+}
+
+/// A texture of one [main: Color] with a different color in each corner:
+/// [ul: Color] upper left, [ur: Color] upper right, [bl: Color] bottom left
+/// and [br: Color] bottom right. Handy to check how a mapping is oriented
+#[derive(Debug, Clone, PartialEq)]
+pub struct UvAlignCheck {
+    pub main: Color,
+    pub ul: Color,
+    pub ur: Color,
+    pub bl: Color,
+    pub br: Color,
+}
+
+impl UvAlignCheck {
+    pub fn new(main: Color, ul: Color, ur: Color, bl: Color, br: Color) -> Self {
+        Self { main, ul, ur, bl, br }
+    }
+}
+
+impl UvPattern for UvAlignCheck {
+    fn uv_pattern_at(&self, u: f64, v: f64) -> Color {
+        // remember: v=0 at the bottom, v=1 at the top
+        if v > 0.8 {
+            if u < 0.2 {
+                return self.ul;
+            }
+            if u > 0.8 {
+                return self.ur;
+            }
+        } else if v < 0.2 {
+            if u < 0.2 {
+                return self.bl;
+            }
+            if u > 0.8 {
+                return self.br;
+            }
+        }
+        self.main
+    }
+
+    fn clone_box(&self) -> Box<dyn UvPattern> {
+        Box::new(self.clone())
+    }
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        match other.downcast_ref::<Self>() {
+            Some(p) => self == p,
+            _ => false
+        }
+    }
+}
+
+/// How an [UvImage] picks the color between the centers of its pixels
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum TextureFilter {
+    /// The color of the closest pixel, blocky when magnified
+    Nearest,
+    /// A blend of the four closest pixels, smooth when magnified
+    Bilinear,
+}
+
+/// A texture read from an image: u goes from the left edge (0) to the
+/// right edge (1) of the [canvas: Arc<Canvas>], v from its bottom (0) to its
+/// top (1). The image is shared, cloning the texture doesn't copy its pixels
+#[derive(Debug, Clone, PartialEq)]
+pub struct UvImage {
+    pub canvas: Arc<Canvas>,
+    pub filter: TextureFilter,
+}
+
+impl UvImage {
+    /// Make a texture of a [Canvas], or of an [Arc<Canvas>] to share
+    /// the same image between several textures. It can't be empty
+    pub fn new(canvas: impl Into<Arc<Canvas>>, filter: TextureFilter) -> Result<Self, String> {
+        let canvas = canvas.into();
+        if canvas.width == 0 || canvas.height == 0 {
+            return Err(format!("a texture needs at least one pixel, the image is {}x{}", canvas.width, canvas.height));
+        }
+        Ok(Self { canvas, filter })
+    }
+
+    /// Load the texture from an image file (PNG, JPEG, ...)
+    pub fn from_file(file_name: &str, filter: TextureFilter) -> Result<Self, String> {
+        Self::new(Canvas::load(file_name)?, filter)
+    }
+}
+
+impl UvPattern for UvImage {
+    fn uv_pattern_at(&self, u: f64, v: f64) -> Color {
+        // flip v over so it matches the image layout, with y at the top
+        let v = 1.0 - v;
+        let x = u * (self.canvas.width - 1) as f64;
+        let y = v * (self.canvas.height - 1) as f64;
+        match self.filter {
+            TextureFilter::Nearest => self.canvas[(x.round() as usize, y.round() as usize)],
+            TextureFilter::Bilinear => {
+                // the canvas clamps the indices, so the
+                // pixels past the last column and row are fine
+                let (x0, y0) = (x.floor(), y.floor());
+                let (fx, fy) = (x - x0, y - y0);
+                let (x0, y0) = (x0 as usize, y0 as usize);
+                let top = self.canvas[(x0, y0)] * (1.0 - fx) + self.canvas[(x0 + 1, y0)] * fx;
+                let bottom = self.canvas[(x0, y0 + 1)] * (1.0 - fx) + self.canvas[(x0 + 1, y0 + 1)] * fx;
+                top * (1.0 - fy) + bottom * fy
+            }
+        }
+    }
+
+    fn clone_box(&self) -> Box<dyn UvPattern> {
+        Box::new(self.clone())
+    }
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        match other.downcast_ref::<Self>() {
+            Some(p) => self == p,
+            _ => false
+        }
+    }
+}
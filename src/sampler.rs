@@ -0,0 +1,149 @@
+use std::f64::consts::PI;
+
+/// How the camera places the samples inside a pixel. Every sample is an
+/// (x, y) offset from the top left corner of the pixel, between 0 and 1.
+/// [Grid] splits the pixel in cells as close to square as the number of
+/// samples allows (16 samples are a 4x4 grid, 8 a 2x4 one) and samples
+/// their centers. [Jittered] moves every sample around inside its cell,
+/// trading the regular aliasing of the grid for a bit of noise. [Halton]
+/// spreads the samples with the low-discrepancy Halton sequence in bases
+/// 2 and 3, which covers the pixel evenly for any number of samples.
+/// All of them are deterministic: a pixel always gets the same samples
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Sampler {
+    #[default]
+    Grid,
+    Jittered,
+    Halton,
+}
+
+impl Sampler {
+    /// Return the offsets of the [samples: usize] samples of the pixel [x, y]
+    pub fn offsets(&self, samples: usize, x: usize, y: usize) -> Vec<(f64, f64)> {
+        let samples = samples.max(1);
+        match self {
+            Sampler::Grid | Sampler::Jittered => {
+                let (cols, rows) = grid(samples);
+                (0..samples)
+                    .map(|i| {
+                        let (du, dv) = match self {
+                            Sampler::Jittered => jitter(&[(x as u64) << 32 | y as u64, i as u64]),
+                            _ => (0.5, 0.5),
+                        };
+                        (
+                            ((i % cols) as f64 + du) / cols as f64,
+                            ((i / cols) as f64 + dv) / rows as f64,
+                        )
+                    })
+                    .collect()
+            }
+            // skip the first point of the sequence, it is always the corner
+            Sampler::Halton => (1..=samples)
+                .map(|i| (halton(i, 2), halton(i, 3)))
+                .collect(),
+        }
+    }
+}
+
+/// How the samples of a pixel are combined into its color. Each sample is
+/// weighted by how far it is from the center of the pixel: [Box] gives all
+/// of them the same weight, [Tent] falls linearly from 1 at the center to
+/// 0 one pixel away, and [Gaussian] follows a bell curve with the given
+/// standard deviation, in pixels. Build it with [Filter::gaussian], which
+/// checks the deviation is positive: a [Gaussian] with a deviation that
+/// isn't a positive number weights every sample the same, like [Box]
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum Filter {
+    #[default]
+    Box,
+    Tent,
+    Gaussian(f64),
+}
+
+impl Filter {
+    /// Return a [Gaussian] filter with the standard deviation [sigma: f64], in pixels
+    pub fn gaussian(sigma: f64) -> Result<Self, String> {
+        if sigma > 0.0 && sigma.is_finite() {
+            Ok(Filter::Gaussian(sigma))
+        } else {
+            Err(format!("the deviation of a gaussian filter must be a positive number, found {}", sigma))
+        }
+    }
+
+    /// Return the weight of a sample [dx, dy] pixels away from the center
+    pub fn weight(&self, dx: f64, dy: f64) -> f64 {
+        match *self {
+            Filter::Box => 1.0,
+            Filter::Tent => (1.0 - dx.abs()).max(0.0) * (1.0 - dy.abs()).max(0.0),
+            Filter::Gaussian(sigma) if sigma > 0.0 && sigma.is_finite() => {
+                let s2 = 2.0 * sigma * sigma;
+                (-(dx * dx + dy * dy) / s2).exp() / (PI * s2)
+            }
+            Filter::Gaussian(_) => 1.0,
+        }
+    }
+}
+
+/// Settings of adaptive anti-aliasing: the image is first rendered with one
+/// sample per pixel, then the pixels that differ from one of their neighbors
+/// by more than [threshold: f64] in any channel are sampled again, with 4
+/// times as many samples each round, for at most [max_depth: usize] rounds
+/// (so up to 4^max_depth samples). Flat areas keep their single sample
+#[derive(Debug, Copy, Clone, PartialEq)]
+pub struct Adaptive {
+    pub threshold: f64,
+    pub max_depth: usize,
+}
+
+impl Adaptive {
+    pub fn new(threshold: f64, max_depth: usize) -> Self {
+        Self { threshold, max_depth }
+    }
+}
+
+impl Default for Adaptive {
+    fn default() -> Self {
+        Self::new(0.1, 3)
+    }
+}
+
+/// Return the [index: usize] element of the Halton sequence in the given [base]
+pub fn halton(index: usize, base: usize) -> f64 {
+    let mut f = 1.0;
+    let mut r = 0.0;
+    let mut i = index;
+    while i > 0 {
+        f /= base as f64;
+        r += f * (i % base) as f64;
+        i /= base;
+    }
+    r
+}
+
+/// Split [samples: usize] in a grid of (columns, rows) cells as close to
+/// square as the number allows: 16 samples are a 4x4 grid, 8 a 2x4 one
+pub fn grid(samples: usize) -> (usize, usize) {
+    let samples = samples.max(1);
+    // the largest divisor of samples that is not above its square root
+    let mut cols = (samples as f64).sqrt() as usize;
+    while samples % cols != 0 {
+        cols -= 1;
+    }
+    (cols, samples / cols)
+}
+
+/// Return two pseudo random offsets between 0 and 1 made from the [keys],
+/// like the pixel and the index of a sample. It hashes them instead of keeping
+/// a random generator around, so the same keys always get the same offsets
+pub fn jitter(keys: &[u64]) -> (f64, f64) {
+    // splitmix64 finalizer
+    fn mix(mut z: u64) -> u64 {
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58476d1ce4e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d049bb133111eb);
+        z ^ (z >> 31)
+    }
+    let h = keys.iter().fold(0, |h, &k| mix(h ^ k));
+    // the top 53 bits make an evenly spread f64 in 0..1
+    let to_unit = |x: u64| (x >> 11) as f64 / (1u64 << 53) as f64;
+    (to_unit(h), to_unit(mix(h)))
+}
@@ -0,0 +1,629 @@
+use std::cell::RefCell;
+use std::collections::HashMap;
+use std::fs;
+use std::sync::Arc;
+
+use yaml_rust2::parser::{Event, MarkedEventReceiver, Parser};
+use yaml_rust2::scanner::Marker;
+
+use crate::camera::Camera;
+use crate::color::Color;
+use crate::light::{AreaLight, Attenuation, DirectionalLight, Light, LightSource, SpotLight};
+use crate::material::Material;
+use crate::matrix::mat4::{identity, rotate_x, rotate_y, rotate_z, scale, skew, translate, view_transform};
+use crate::matrix::Mat4;
+use crate::obj::ObjParser;
+use crate::pattern::{Checker, Gradient, Pattern, Ring, Stripe};
+use crate::shape::{Cone, Csg, CsgOperation, Cube, Cylinder, Group, Plane, Shape, Sphere};
+use crate::tuple::{Point, Vector};
+use crate::world::World;
+
+/// A scene described in the YAML dialect of The Ray Tracer Challenge: a list
+/// of items, each one either adding something to the scene (`add: camera`,
+/// `add: light`, `add: sphere`, ...) or defining a reusable material,
+/// transform or shape (`define: name`) that later items refer to by name.
+/// A definition can `extend` an earlier one, overriding some of its keys.
+/// Loading it builds the [camera: Camera] and the [world: World] it describes
+#[derive(Debug, Clone)]
+pub struct Scene {
+    pub camera: Camera,
+    pub world: World,
+}
+
+impl Scene {
+    /// Read and parse the scene file at the given [path]
+    pub fn from_file(path: &str) -> Result<Self, String> {
+        match fs::read_to_string(path) {
+            Ok(content) => Self::parse(&content).map_err(|e| format!("{}: {}", path, e)),
+            Err(e) => Err(format!("Error reading the scene file {}: {}", path, e)),
+        }
+    }
+
+    /// Parse a scene from its YAML [source]. The errors name
+    /// the line and the key that could not be used
+    pub fn parse(source: &str) -> Result<Self, String> {
+        let mut builder = TreeBuilder::default();
+        Parser::new(source.chars())
+            .load(&mut builder, false)
+            .map_err(|e| e.to_string())?;
+        if let Some(e) = builder.error {
+            return Err(e);
+        }
+        let items = match builder.root {
+            Some(Node::Seq(items, _)) => items,
+            Some(node) => return Err(error(node.line(), "", "a scene must be a list of items")),
+            None => return Err(String::from("the scene is empty")),
+        };
+
+        let mut loader = Loader::default();
+        for item in &items {
+            loader.item(item)?;
+        }
+        match loader.camera {
+            Some(camera) => Ok(Self {
+                camera,
+                world: World::new(Some(loader.lights), Some(loader.objects)),
+            }),
+            None => Err(String::from("the scene has no camera, add one with `add: camera`")),
+        }
+    }
+}
+
+/// A YAML value, with the 1-based line where it starts. Mappings keep
+/// their keys in order, each with the line it is on
+#[derive(Debug, Clone)]
+enum Node {
+    Scalar(String, usize),
+    Seq(Vec<Node>, usize),
+    Map(Vec<(String, usize, Node)>, usize),
+}
+
+impl Node {
+    fn line(&self) -> usize {
+        match self {
+            Node::Scalar(_, line) | Node::Seq(_, line) | Node::Map(_, line) => *line,
+        }
+    }
+}
+
+/// Collect the events of the YAML parser in a tree of [Node]s
+#[derive(Debug, Default)]
+struct TreeBuilder {
+    stack: Vec<(Node, Option<(String, usize)>)>,
+    root: Option<Node>,
+    error: Option<String>,
+}
+
+impl TreeBuilder {
+    fn push(&mut self, node: Node) {
+        match self.stack.last_mut() {
+            None => self.root = Some(node),
+            Some((Node::Seq(items, _), _)) => items.push(node),
+            Some((Node::Map(entries, _), key)) => match (key.take(), node) {
+                (Some((k, line)), node) => entries.push((k, line, node)),
+                (None, Node::Scalar(k, line)) => *key = Some((k, line)),
+                (None, node) => self.fail(node.line(), "keys must be plain strings"),
+            },
+            Some((Node::Scalar(..), _)) => unreachable!(),
+        }
+    }
+
+    fn fail(&mut self, line: usize, message: &str) {
+        if self.error.is_none() {
+            self.error = Some(error(line, "", message));
+        }
+    }
+}
+
+impl MarkedEventReceiver for TreeBuilder {
+    fn on_event(&mut self, ev: Event, mark: Marker) {
+        let line = mark.line();
+        match ev {
+            Event::Scalar(value, ..) => self.push(Node::Scalar(value, line)),
+            Event::SequenceStart(..) => self.stack.push((Node::Seq(vec![], line), None)),
+            Event::MappingStart(..) => self.stack.push((Node::Map(vec![], line), None)),
+            Event::SequenceEnd | Event::MappingEnd => {
+                if let Some((node, _)) = self.stack.pop() {
+                    self.push(node);
+                }
+            }
+            Event::Alias(_) => self.fail(line, "aliases are not supported, use `define` instead"),
+            _ => {}
+        }
+    }
+}
+
+/// Return an error message for the given [line] and [key]
+fn error(line: usize, key: &str, message: &str) -> String {
+    if key.is_empty() {
+        format!("line {}: {}", line, message)
+    } else {
+        format!("line {}, key `{}`: {}", line, key, message)
+    }
+}
+
+fn scalar<'a>(key: &str, node: &'a Node) -> Result<&'a str, String> {
+    match node {
+        Node::Scalar(s, _) => Ok(s),
+        _ => Err(error(node.line(), key, "expected a single value")),
+    }
+}
+
+fn number(key: &str, node: &Node) -> Result<f64, String> {
+    let s = scalar(key, node)?;
+    s.parse::<f64>()
+        .map_err(|_| error(node.line(), key, &format!("expected a number, found `{}`", s)))
+}
+
+fn integer(key: &str, node: &Node) -> Result<usize, String> {
+    let s = scalar(key, node)?;
+    match s.parse::<usize>() {
+        Ok(n) if n > 0 => Ok(n),
+        _ => Err(error(node.line(), key, &format!("expected a positive integer, found `{}`", s))),
+    }
+}
+
+fn boolean(key: &str, node: &Node) -> Result<bool, String> {
+    match scalar(key, node)? {
+        "true" => Ok(true),
+        "false" => Ok(false),
+        s => Err(error(node.line(), key, &format!("expected true or false, found `{}`", s))),
+    }
+}
+
+fn triple(key: &str, node: &Node) -> Result<(f64, f64, f64), String> {
+    match node {
+        Node::Seq(items, _) if items.len() == 3 => {
+            Ok((number(key, &items[0])?, number(key, &items[1])?, number(key, &items[2])?))
+        }
+        _ => Err(error(node.line(), key, "expected a list of 3 numbers")),
+    }
+}
+
+fn point(key: &str, node: &Node) -> Result<Point, String> {
+    triple(key, node).map(|(x, y, z)| Point::new(x, y, z))
+}
+
+fn vector(key: &str, node: &Node) -> Result<Vector, String> {
+    triple(key, node).map(|(x, y, z)| Vector::new(x, y, z))
+}
+
+fn color(key: &str, node: &Node) -> Result<Color, String> {
+    triple(key, node).map(|(r, g, b)| Color::new(r, g, b))
+}
+
+fn entries<'a>(key: &str, node: &'a Node) -> Result<&'a [(String, usize, Node)], String> {
+    match node {
+        Node::Map(entries, _) => Ok(entries),
+        _ => Err(error(node.line(), key, "expected a mapping of keys and values")),
+    }
+}
+
+/// Return the mapping [over] applied on top of the mapping [base]:
+/// the keys of [over] replace the same keys of [base]
+fn merge(base: &Node, over: &Node) -> Node {
+    match (base, over) {
+        (Node::Map(base, _), Node::Map(over, line)) => {
+            let mut merged: Vec<_> = base
+                .iter()
+                .filter(|(k, _, _)| over.iter().all(|(o, _, _)| o != k))
+                .cloned()
+                .collect();
+            merged.extend(over.iter().cloned());
+            Node::Map(merged, *line)
+        }
+        _ => over.clone(),
+    }
+}
+
+/// Return an error if a required key was not found in the item at [line]
+fn required<T>(value: Option<T>, line: usize, key: &str, what: &str) -> Result<T, String> {
+    value.ok_or_else(|| error(line, key, &format!("missing from the {}", what)))
+}
+
+/// The state of the scene while its items are read one by one. The
+/// definitions being expanded are kept in [expanding], in order, so
+/// one that refers to itself is an error instead of an endless loop
+#[derive(Debug, Default)]
+struct Loader {
+    defines: HashMap<String, Node>,
+    expanding: RefCell<Vec<String>>,
+    camera: Option<Camera>,
+    lights: Vec<Box<dyn LightSource>>,
+    objects: Vec<Box<dyn Shape>>,
+}
+
+impl Loader {
+    /// Call [expand] while the definition [name] is being expanded,
+    /// or fail if [name] is already being expanded further up
+    fn expanding<T>(&self, name: &str, line: usize, key: &str, expand: impl FnOnce() -> Result<T, String>) -> Result<T, String> {
+        if self.expanding.borrow().iter().any(|n| n == name) {
+            let mut chain = self.expanding.borrow().clone();
+            chain.push(name.to_string());
+            let start = chain.iter().position(|n| n == name).unwrap_or(0);
+            return Err(error(line, key, &format!(
+                "`{}` is defined in terms of itself ({})", name, chain[start..].join(" -> "))));
+        }
+        self.expanding.borrow_mut().push(name.to_string());
+        let result = expand();
+        self.expanding.borrow_mut().pop();
+        result
+    }
+
+    /// Read one of the top level items of the scene
+    fn item(&mut self, item: &Node) -> Result<(), String> {
+        let fields = entries("", item)?;
+        let Some((key, line, value)) = fields.first() else {
+            return Err(error(item.line(), "", "empty item"));
+        };
+        match key.as_str() {
+            "add" => match scalar(key, value)? {
+                "camera" => self.camera = Some(self.camera(item)?),
+                "light" => self.lights.push(self.light(item)?),
+                "spot-light" => self.lights.push(Box::new(self.spot_light(item)?)),
+                "directional-light" => self.lights.push(Box::new(self.directional_light(item)?)),
+                _ => {
+                    let shape = self.shape(item)?;
+                    self.objects.push(shape);
+                }
+            },
+            "define" => self.define(item)?,
+            _ => return Err(error(*line, key, "an item must start with `add` or `define`")),
+        }
+        Ok(())
+    }
+
+    /// Remember a named material, transform or shape. With
+    /// `extend` it starts from an earlier definition
+    fn define(&mut self, item: &Node) -> Result<(), String> {
+        let mut name = None;
+        let mut base = None;
+        let mut value = None;
+        for (key, line, node) in entries("", item)? {
+            match key.as_str() {
+                "define" => name = Some(scalar(key, node)?.to_string()),
+                "extend" => {
+                    let parent = scalar(key, node)?;
+                    match self.defines.get(parent) {
+                        Some(parent) => base = Some(parent.clone()),
+                        None => return Err(error(*line, key, &format!("`{}` is not defined", parent))),
+                    }
+                }
+                "value" => value = Some(node),
+                _ => return Err(error(*line, key, "unknown key for a definition")),
+            }
+        }
+        let value = required(value, item.line(), "value", "definition")?;
+        let value = match base {
+            Some(base) => merge(&base, value),
+            None => value.clone(),
+        };
+        self.defines.insert(name.unwrap_or_default(), value);
+        Ok(())
+    }
+
+    fn camera(&self, item: &Node) -> Result<Camera, String> {
+        let (mut width, mut height, mut fov) = (None, None, None);
+        let mut from = Point::new(0., 0., -5.);
+        let mut to = Point::new(0., 0., 0.);
+        let mut up = Vector::new(0., 1., 0.);
+        for (key, line, node) in entries("", item)? {
+            match key.as_str() {
+                "add" => {}
+                "width" => width = Some(integer(key, node)?),
+                "height" => height = Some(integer(key, node)?),
+                "field-of-view" => fov = Some(number(key, node)?),
+                "from" => from = point(key, node)?,
+                "to" => to = point(key, node)?,
+                "up" => up = vector(key, node)?,
+                _ => return Err(error(*line, key, "unknown key for a camera")),
+            }
+        }
+        let mut camera = Camera::new(
+            required(width, item.line(), "width", "camera")?,
+            required(height, item.line(), "height", "camera")?,
+            required(fov, item.line(), "field-of-view", "camera")?,
+        );
+        camera.set_transform(view_transform(from, to, up));
+        Ok(camera)
+    }
+
+    /// A point light at `at`, or an area light when `corner`,
+    /// `uvec` and `vvec` are given, sampled `usteps` x `vsteps` times
+    fn light(&self, item: &Node) -> Result<Box<dyn LightSource>, String> {
+        let (mut at, mut intensity, mut corner, mut uvec, mut vvec) = (None, None, None, None, None);
+        let (mut usteps, mut vsteps, mut jitter) = (1, 1, true);
+        let mut attenuation = Attenuation::None;
+        for (key, line, node) in entries("", item)? {
+            match key.as_str() {
+                "add" => {}
+                "at" => at = Some(point(key, node)?),
+                "intensity" => intensity = Some(color(key, node)?),
+                "corner" => corner = Some(point(key, node)?),
+                "uvec" => uvec = Some(vector(key, node)?),
+                "vvec" => vvec = Some(vector(key, node)?),
+                "usteps" => usteps = integer(key, node)?,
+                "vsteps" => vsteps = integer(key, node)?,
+                "jitter" => jitter = boolean(key, node)?,
+                "attenuation" => attenuation = self.attenuation(key, node)?,
+                _ => return Err(error(*line, key, "unknown key for a light")),
+            }
+        }
+        let intensity = required(intensity, item.line(), "intensity", "light")?;
+        match (corner, uvec, vvec) {
+            (Some(corner), Some(uvec), Some(vvec)) => {
+                let mut light = AreaLight::new(corner, uvec, usteps, vvec, vsteps, intensity);
+                light.jitter = jitter;
+                light.attenuation = attenuation;
+                Ok(Box::new(light))
+            }
+            (None, None, None) => {
+                let mut light = Light::new(required(at, item.line(), "at", "light")?, intensity);
+                light.attenuation = attenuation;
+                Ok(Box::new(light))
+            }
+            _ => Err(error(item.line(), "corner", "an area light needs `corner`, `uvec` and `vvec`")),
+        }
+    }
+
+    fn spot_light(&self, item: &Node) -> Result<SpotLight, String> {
+        let (mut at, mut direction, mut inner, mut outer, mut intensity) = (None, None, None, None, None);
+        let mut attenuation = Attenuation::None;
+        for (key, line, node) in entries("", item)? {
+            match key.as_str() {
+                "add" => {}
+                "at" => at = Some(point(key, node)?),
+                "direction" => direction = Some(vector(key, node)?),
+                "inner-angle" => inner = Some(number(key, node)?),
+                "outer-angle" => outer = Some(number(key, node)?),
+                "intensity" => intensity = Some(color(key, node)?),
+                "attenuation" => attenuation = self.attenuation(key, node)?,
+                _ => return Err(error(*line, key, "unknown key for a spot light")),
+            }
+        }
+        let mut light = SpotLight::new(
+            required(at, item.line(), "at", "spot light")?,
+            required(direction, item.line(), "direction", "spot light")?,
+            required(inner, item.line(), "inner-angle", "spot light")?,
+            required(outer, item.line(), "outer-angle", "spot light")?,
+            required(intensity, item.line(), "intensity", "spot light")?,
+        );
+        light.attenuation = attenuation;
+        Ok(light)
+    }
+
+    fn directional_light(&self, item: &Node) -> Result<DirectionalLight, String> {
+        let (mut direction, mut intensity) = (None, None);
+        for (key, line, node) in entries("", item)? {
+            match key.as_str() {
+                "add" => {}
+                "direction" => direction = Some(vector(key, node)?),
+                "intensity" => intensity = Some(color(key, node)?),
+                _ => return Err(error(*line, key, "unknown key for a directional light")),
+            }
+        }
+        Ok(DirectionalLight::new(
+            required(direction, item.line(), "direction", "directional light")?,
+            required(intensity, item.line(), "intensity", "directional light")?,
+        ))
+    }
+
+    /// `[none]`, `[linear, range]` or `[inverse-square, radius]`
+    fn attenuation(&self, key: &str, node: &Node) -> Result<Attenuation, String> {
+        let items = match node {
+            Node::Seq(items, _) if !items.is_empty() => items,
+            _ => return Err(error(node.line(), key, "expected a list like [inverse-square, 1]")),
+        };
+        let attenuation = match (scalar(key, &items[0])?, items.len()) {
+            ("none", 1) => Ok(Attenuation::None),
+            ("linear", 2) => Attenuation::linear(number(key, &items[1])?),
+            ("inverse-square", 2) => Attenuation::inverse_square(number(key, &items[1])?),
+            (model, _) => return Err(error(node.line(), key, &format!("unknown attenuation `{}`", model))),
+        };
+        attenuation.map_err(|e| error(node.line(), key, &e))
+    }
+
+    /// Build the shape described by the [item]. Besides the built-in
+    /// shapes, `add` can name a shape made with `define`: the keys of the
+    /// item replace the ones of the definition
+    fn shape(&self, item: &Node) -> Result<Box<dyn Shape>, String> {
+        let fields = entries("", item)?;
+        let (kind, kind_line) = match fields.iter().find(|(k, _, _)| k == "add") {
+            Some((key, line, node)) => (scalar(key, node)?, *line),
+            None => return Err(error(item.line(), "add", "missing from the shape")),
+        };
+        if let Some(defined @ Node::Map(..)) = self.defines.get(kind) {
+            let without_add = match item {
+                Node::Map(entries, line) => {
+                    Node::Map(entries.iter().filter(|(k, _, _)| k != "add").cloned().collect(), *line)
+                }
+                _ => item.clone(),
+            };
+            return self.expanding(kind, kind_line, "add", || self.shape(&merge(defined, &without_add)));
+        }
+
+        let mut transform = identity();
+        let mut material = None;
+        let (mut minimum, mut maximum, mut closed) = (None, None, None);
+        let (mut children, mut file) = (vec![], None);
+        let (mut operation, mut left, mut right) = (None, None, None);
+        for (key, line, node) in fields {
+            match key.as_str() {
+                "add" => {}
+                "transform" => transform = self.transform(key, node)?,
+                "material" => material = Some(self.material(key, node)?),
+                "min" => minimum = Some(number(key, node)?),
+                "max" => maximum = Some(number(key, node)?),
+                "closed" => closed = Some(boolean(key, node)?),
+                "children" => match node {
+                    Node::Seq(items, _) => {
+                        for child in items {
+                            children.push(self.shape(child)?);
+                        }
+                    }
+                    _ => return Err(error(*line, key, "expected a list of shapes")),
+                },
+                "file" => file = Some(scalar(key, node)?),
+                "operation" => {
+                    operation = Some(match scalar(key, node)? {
+                        "union" => CsgOperation::Union,
+                        "intersection" => CsgOperation::Intersection,
+                        "difference" => CsgOperation::Difference,
+                        op => return Err(error(*line, key, &format!("unknown operation `{}`", op))),
+                    })
+                }
+                "left" => left = Some(self.shape(node)?),
+                "right" => right = Some(self.shape(node)?),
+                "shadow" => return Err(error(*line, key, "shapes that cast no shadow are not supported")),
+                _ => return Err(error(*line, key, &format!("unknown key for a {}", kind))),
+            }
+        }
+
+        let has_cylinder_keys = minimum.is_some() || maximum.is_some() || closed.is_some();
+        if has_cylinder_keys && kind != "cylinder" && kind != "cone" {
+            return Err(error(kind_line, "add", &format!("a {} has no `min`, `max` or `closed`", kind)));
+        }
+        let mut shape: Box<dyn Shape> = match kind {
+            "sphere" => Box::new(Sphere::new(Some(transform), material.clone())),
+            "plane" => Box::new(Plane::new(Some(transform), material.clone())),
+            "cube" => Box::new(Cube::new(Some(transform), material.clone())),
+            "cylinder" => {
+                let mut c = Cylinder::new(Some(transform), material.clone());
+                c.minimum = minimum.unwrap_or(c.minimum);
+                c.maximum = maximum.unwrap_or(c.maximum);
+                c.closed = closed.unwrap_or(c.closed);
+                Box::new(c)
+            }
+            "cone" => {
+                let mut c = Cone::new(Some(transform), material.clone());
+                c.minimum = minimum.unwrap_or(c.minimum);
+                c.maximum = maximum.unwrap_or(c.maximum);
+                c.closed = closed.unwrap_or(c.closed);
+                Box::new(c)
+            }
+            "group" => {
+                let mut g = Group::new(Some(transform));
+                g.add_children(children);
+                Box::new(g)
+            }
+            "obj" => {
+                let file = required(file, kind_line, "file", "obj")?;
+                let mut g = ObjParser::from_file(file)
+                    .map_err(|e| error(kind_line, "file", &e))?
+                    .into_group();
+                g.set_transform(transform);
+                Box::new(g)
+            }
+            "csg" => {
+                let mut csg = Csg::new(
+                    required(operation, kind_line, "operation", "csg")?,
+                    required(left, kind_line, "left", "csg")?,
+                    required(right, kind_line, "right", "csg")?,
+                );
+                csg.set_transform(transform);
+                Box::new(csg)
+            }
+            _ => return Err(error(kind_line, "add", &format!("unknown shape `{}`", kind))),
+        };
+        // groups pass their material on to the children,
+        // so they only get one when the scene asks for it
+        if let (Some(material), "group" | "obj" | "csg") = (material, kind) {
+            shape.set_material(material);
+        }
+        Ok(shape)
+    }
+
+    /// A material is either the name of a definition or
+    /// a mapping of its attributes, which may extend one
+    fn material(&self, key: &str, node: &Node) -> Result<Material, String> {
+        let node = match node {
+            Node::Scalar(name, line) => match self.defines.get(name) {
+                Some(defined) => defined,
+                None => return Err(error(*line, key, &format!("`{}` is not defined", name))),
+            },
+            node => node,
+        };
+        let mut m = Material::default();
+        for (key, line, node) in entries(key, node)? {
+            match key.as_str() {
+                "color" => m.color = color(key, node)?,
+                "ambient" => m.ambient = number(key, node)?,
+                "diffuse" => m.diffuse = number(key, node)?,
+                "specular" => m.specular = number(key, node)?,
+                "shininess" => m.shininess = number(key, node)?,
+                "reflective" => m.reflective = number(key, node)?,
+                "transparency" => m.transparency = number(key, node)?,
+                "refractive-index" => m.refractive_index = number(key, node)?,
+                "pattern" => m.pattern = Some(Arc::from(self.pattern(key, node)?)),
+                _ => return Err(error(*line, key, "unknown key for a material")),
+            }
+        }
+        Ok(m)
+    }
+
+    fn pattern(&self, key: &str, node: &Node) -> Result<Box<dyn Pattern>, String> {
+        let (mut kind, mut colors, mut transform) = (None, None, None);
+        for (key, line, node) in entries(key, node)? {
+            match key.as_str() {
+                "type" => kind = Some((scalar(key, node)?, *line)),
+                "colors" => match node {
+                    Node::Seq(items, _) if items.len() == 2 => {
+                        colors = Some((color(key, &items[0])?, color(key, &items[1])?))
+                    }
+                    _ => return Err(error(*line, key, "expected a list of 2 colors")),
+                },
+                "transform" => transform = Some(self.transform(key, node)?),
+                _ => return Err(error(*line, key, "unknown key for a pattern")),
+            }
+        }
+        let (kind, line) = required(kind, node.line(), "type", "pattern")?;
+        let (a, b) = required(colors, node.line(), "colors", "pattern")?;
+        Ok(match kind {
+            "stripes" => Box::new(Stripe::new(a, b, transform)),
+            "gradient" => Box::new(Gradient::new(a, b, transform)),
+            "rings" => Box::new(Ring::new(a, b, transform)),
+            "checkers" => Box::new(Checker::new(a, b, transform)),
+            _ => return Err(error(line, "type", &format!("unknown pattern `{}`", kind))),
+        })
+    }
+
+    /// A list of transformations applied in order, the first one first.
+    /// Each one is either like `[translate, 1, 2, 3]` or the name of a
+    /// defined list of transformations
+    fn transform(&self, key: &str, node: &Node) -> Result<Mat4, String> {
+        let items = match node {
+            Node::Seq(items, _) => items,
+            _ => return Err(error(node.line(), key, "expected a list of transformations")),
+        };
+        let mut m = identity();
+        for item in items {
+            let t = match item {
+                Node::Scalar(name, line) => match self.defines.get(name) {
+                    Some(defined) => self.expanding(name, *line, key, || self.transform(key, defined))?,
+                    None => return Err(error(*line, key, &format!("`{}` is not defined", name))),
+                },
+                Node::Seq(parts, line) if !parts.is_empty() => {
+                    let args = parts[1..]
+                        .iter()
+                        .map(|a| number(key, a))
+                        .collect::<Result<Vec<f64>, String>>()?;
+                    match (scalar(key, &parts[0])?, args.as_slice()) {
+                        ("translate", &[x, y, z]) => translate(x, y, z),
+                        ("scale", &[x, y, z]) => scale(x, y, z),
+                        ("rotate-x", &[r]) => rotate_x(r),
+                        ("rotate-y", &[r]) => rotate_y(r),
+                        ("rotate-z", &[r]) => rotate_z(r),
+                        ("shear", &[xy, xz, yx, yz, zx, zy]) => skew(xy, xz, yx, yz, zx, zy),
+                        (op, _) => {
+                            return Err(error(*line, key, &format!(
+                                "unknown transformation `{}` with {} arguments", op, args.len())))
+                        }
+                    }
+                }
+                _ => return Err(error(item.line(), key, "expected a transformation like [scale, 1, 2, 3]")),
+            };
+            m = t * m;
+        }
+        Ok(m)
+    }
+}
@@ -0,0 +1,176 @@
+use crate::bounds::Bounds;
+use crate::get_id;
+use crate::intersection::{Intersection, Intersections};
+use crate::material::Material;
+use crate::matrix::Mat4;
+use crate::ray::Ray;
+use crate::tuple::Point;
+use crate::tuple::Vector;
+use crate::EPSILON;
+
+// `Any` allows us to do dynamic typecasting.
+use std::any::Any;
+use super::cylinder::check_cap;
+use super::shape::Shape;
+use super::transform::ShapeTransform;
+
+
+/// A double-napped cone centered on the y axis, whose radius at any y is |y|.
+/// Like the [Cylinder](super::Cylinder) it is infinite by default, but it can be
+/// truncated between [minimum: f64] and [maximum: f64] and, when [closed: bool]
+/// is true, capped at both ends.
+#[derive(Debug, Clone)]
+pub struct Cone {
+    pub id: usize,
+    pub minimum: f64,
+    pub maximum: f64,
+    pub closed: bool,
+    transform: ShapeTransform,
+    material: Material,
+}
+
+impl Cone {
+    pub fn new(transform: Option<Mat4>, material: Option<Material>) -> Self {
+        let t = transform.unwrap_or(Mat4::identity());
+        Self {
+            id: get_id(),
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+            transform: ShapeTransform::new(t),
+            material: material.unwrap_or_default(),
+        }
+    }
+
+    /// This function adds to [xs] the intersections of the ray with the
+    /// end caps, if the cone is closed. The radius of each cap is the
+    /// absolute value of the y coordinate where it sits.
+    fn intersect_caps(&self, ray: Ray, xs: &mut Vec<Intersection>) {
+        if !self.closed || crate::f64eq(0.0, ray.direction.y) {
+            return;
+        }
+        let t = (self.minimum - ray.origin.y) / ray.direction.y;
+        if check_cap(ray, t, self.minimum.abs()) {
+            xs.push(Intersection::new(t, self.clone_box()));
+        }
+        let t = (self.maximum - ray.origin.y) / ray.direction.y;
+        if check_cap(ray, t, self.maximum.abs()) {
+            xs.push(Intersection::new(t, self.clone_box()));
+        }
+    }
+
+    /// This function pushes into [xs] the intersection at [t]
+    /// if it lies between the truncation bounds
+    fn push_if_in_bounds(&self, ray: Ray, t: f64, xs: &mut Vec<Intersection>) {
+        let y = ray.origin.y + t * ray.direction.y;
+        if self.minimum < y && y < self.maximum {
+            xs.push(Intersection::new(t, self.clone_box()));
+        }
+    }
+}
+
+impl PartialEq for Cone {
+
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Shape for Cone {
+
+    fn local_intersect(&self, ray: Ray) -> Intersections {
+        let mut xs = vec![];
+
+        let a = ray.direction.x.powi(2) - ray.direction.y.powi(2) + ray.direction.z.powi(2);
+        let b = 2.0 * ray.origin.x * ray.direction.x - 2.0 * ray.origin.y * ray.direction.y
+            + 2.0 * ray.origin.z * ray.direction.z;
+        let c = ray.origin.x.powi(2) - ray.origin.y.powi(2) + ray.origin.z.powi(2);
+
+        if crate::f64eq(0.0, a) {
+            // the ray is parallel to one of the cone's halves, so it
+            // crosses the other half once (unless it misses entirely)
+            if !crate::f64eq(0.0, b) {
+                self.push_if_in_bounds(ray, -c / (2.0 * b), &mut xs);
+            }
+        } else {
+            let discriminant = b.powi(2) - 4.0 * a * c;
+            // a tiny negative discriminant is a ray grazing the apex
+            if discriminant >= -EPSILON {
+                let discriminant = discriminant.max(0.0);
+                let mut t0 = (-b - discriminant.sqrt()) / (2.0 * a);
+                let mut t1 = (-b + discriminant.sqrt()) / (2.0 * a);
+                if t0 > t1 {
+                    std::mem::swap(&mut t0, &mut t1);
+                }
+                self.push_if_in_bounds(ray, t0, &mut xs);
+                self.push_if_in_bounds(ray, t1, &mut xs);
+            }
+        }
+
+        self.intersect_caps(ray, &mut xs);
+        Intersections::new(xs)
+    }
+
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        match other.downcast_ref::<Self>() {
+            Some(s) => self.id == s.id,
+            _ => false
+        }
+    }
+
+    fn local_normal_at(&self, object_point:Point) -> Vector {
+        let dist = object_point.x.powi(2) + object_point.z.powi(2);
+        if dist < self.maximum.powi(2) && object_point.y >= self.maximum - EPSILON {
+            Vector::new(0.0, 1.0, 0.0)
+        } else if dist < self.minimum.powi(2) && object_point.y <= self.minimum + EPSILON {
+            Vector::new(0.0, -1.0, 0.0)
+        } else {
+            // the normal of the walls points away from the axis and
+            // toward the apex on the upper nappe, away from it on the lower
+            let mut y = dist.sqrt();
+            if object_point.y > 0.0 {
+                y = -y;
+            }
+            Vector::new(object_point.x, y, object_point.z)
+        }
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+
+    fn material(&self) -> Material {
+        self.material.clone()
+    }
+
+    fn shape_transform(&self) -> &ShapeTransform {
+        &self.transform
+    }
+
+    fn shape_transform_mut(&mut self) -> &mut ShapeTransform {
+        &mut self.transform
+    }
+
+    fn local_bounds(&self) -> Bounds {
+        // the radius of the cone is the largest |y| it reaches
+        let limit = self.minimum.abs().max(self.maximum.abs());
+        Bounds::new(
+            Point::new(-limit, self.minimum, -limit),
+            Point::new(limit, self.maximum, limit),
+        )
+    }
+}
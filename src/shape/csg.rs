@@ -0,0 +1,190 @@
+use crate::bounds::Bounds;
+use crate::get_id;
+use crate::intersection::{Intersection, Intersections};
+use crate::material::Material;
+use crate::matrix::Mat4;
+use crate::ray::Ray;
+use crate::tuple::Point;
+use crate::tuple::Vector;
+
+// `Any` allows us to do dynamic typecasting.
+use std::any::Any;
+use super::shape::Shape;
+use super::transform::ShapeTransform;
+
+
+/// The boolean operation a [Csg] applies to its two children
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum CsgOperation {
+    /// Everything in either child
+    Union,
+    /// Only what is in both children
+    Intersection,
+    /// What is in the left child but not in the right one
+    Difference,
+}
+
+impl CsgOperation {
+    /// This function decides if an intersection must be kept, given whether
+    /// it hit the left child [lhit], and whether at that point the ray is
+    /// inside the left child [inl] and inside the right child [inr]
+    pub fn intersection_allowed(&self, lhit: bool, inl: bool, inr: bool) -> bool {
+        match self {
+            CsgOperation::Union => (lhit && !inr) || (!lhit && !inl),
+            CsgOperation::Intersection => (lhit && inr) || (!lhit && inl),
+            CsgOperation::Difference => (lhit && !inr) || (!lhit && inl),
+        }
+    }
+}
+
+/// A constructive solid geometry shape: it combines a [left] and a [right]
+/// shape with a boolean [operation: CsgOperation]. Both children can be any
+/// shape, including groups and other CSG shapes. Like a [Group](super::Group)
+/// its transform applies to both children. The children are told apart by
+/// their ids, so the same shape (or a clone of it) must not be used on both sides
+#[derive(Debug, Clone)]
+pub struct Csg {
+    pub id: usize,
+    pub operation: CsgOperation,
+    transform: ShapeTransform,
+    material: Material,
+    left: Box<dyn Shape>,
+    right: Box<dyn Shape>,
+}
+
+impl Csg {
+    pub fn new(operation: CsgOperation, left: Box<dyn Shape>, right: Box<dyn Shape>) -> Self {
+        let mut csg = Self {
+            id: get_id(),
+            operation,
+            transform: ShapeTransform::default(),
+            material: Material::default(),
+            left,
+            right,
+        };
+        csg.update_children();
+        csg
+    }
+
+    /// Return the left child
+    pub fn left(&self) -> &dyn Shape {
+        self.left.as_ref()
+    }
+
+    /// Return the right child
+    pub fn right(&self) -> &dyn Shape {
+        self.right.as_ref()
+    }
+
+    /// This function walks the sorted intersections of both children, keeping
+    /// track of whether the ray is inside each of them, and returns only the
+    /// ones on the surface of the combined shape
+    pub fn filter_intersections(&self, xs: Intersections) -> Vec<Intersection> {
+        // begin outside of both children
+        let mut inl = false;
+        let mut inr = false;
+        let mut result = vec![];
+        for i in xs.xs {
+            // if i.object is part of the left child, then lhit is true
+            let lhit = self.left.includes(i.object.id());
+            if self.operation.intersection_allowed(lhit, inl, inr) {
+                result.push(i);
+            }
+            // depending on which object was hit, toggle either inl or inr
+            if lhit {
+                inl = !inl;
+            } else {
+                inr = !inr;
+            }
+        }
+        result
+    }
+
+    /// Push the combined transform of the shape down to its children
+    fn update_children(&mut self) {
+        let t = self.transform.world();
+        self.left.set_parent_transform(t);
+        self.right.set_parent_transform(t);
+    }
+}
+
+impl PartialEq for Csg {
+
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Shape for Csg {
+
+    fn local_intersect(&self, ray: Ray) -> Intersections {
+        // each child converts the ray in its own object space
+        let mut xs = self.left.intersect(ray).xs;
+        xs.extend(self.right.intersect(ray).xs);
+        Intersections::new(self.filter_intersections(Intersections::new(xs)))
+    }
+
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn includes(&self, id: usize) -> bool {
+        self.id == id || self.left.includes(id) || self.right.includes(id)
+    }
+
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        match other.downcast_ref::<Self>() {
+            Some(s) => self.id == s.id,
+            _ => false
+        }
+    }
+
+    fn local_normal_at(&self, _pnt:Point) -> Vector {
+        // intersections always refer to the children, the combined
+        // shape has no surface of its own to compute a normal on
+        panic!("Csg - normal_at can't be called on a csg shape, only on its children");
+    }
+
+    /// Set the material of the shape and of both its children
+    fn set_material(&mut self, material: Material) {
+        self.left.set_material(material.clone());
+        self.right.set_material(material.clone());
+        self.material = material;
+    }
+
+    fn material(&self) -> Material {
+        self.material.clone()
+    }
+
+    fn shape_transform(&self) -> &ShapeTransform {
+        &self.transform
+    }
+
+    fn shape_transform_mut(&mut self) -> &mut ShapeTransform {
+        &mut self.transform
+    }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform.set(transform);
+        self.update_children();
+    }
+
+    fn set_parent_transform(&mut self, t: Mat4) {
+        self.transform.set_parent(t);
+        self.update_children();
+    }
+
+    fn local_bounds(&self) -> Bounds {
+        self.left.bounds().merge(&self.right.bounds())
+    }
+}
@@ -0,0 +1,138 @@
+use crate::bounds::Bounds;
+use crate::get_id;
+use crate::intersection::{Intersection, Intersections};
+use crate::material::Material;
+use crate::matrix::Mat4;
+use crate::ray::Ray;
+use crate::tuple::Point;
+use crate::tuple::Vector;
+use crate::EPSILON;
+
+// `Any` allows us to do dynamic typecasting.
+use std::any::Any;
+use super::shape::Shape;
+use super::transform::ShapeTransform;
+
+
+/// An axis-aligned cube that extends from -1 to +1 along each axis. It contains
+/// an [id: usize] a default identity [transform: Mat4] and a default [material: Material]
+#[derive(Debug, Clone)]
+pub struct Cube {
+    pub id: usize,
+    transform: ShapeTransform,
+    material: Material,
+}
+
+impl Cube {
+    pub fn new(transform: Option<Mat4>, material: Option<Material>) -> Self {
+        let t = transform.unwrap_or(Mat4::identity());
+        Self {
+            id: get_id(),
+            transform: ShapeTransform::new(t),
+            material: material.unwrap_or_default(),
+        }
+    }
+}
+
+/// This function intersects the ray with the pair of planes (slab)
+/// at -1 and +1 along one axis, given the ray's [origin] and [direction]
+/// components for that axis. It returns the (min, max) t values
+fn check_axis(origin: f64, direction: f64) -> (f64, f64) {
+    let tmin_numerator = -1.0 - origin;
+    let tmax_numerator = 1.0 - origin;
+
+    // a ray parallel to the slab never crosses its planes: the
+    // infinite values make sure the other axes decide the result
+    let (tmin, tmax) = if direction.abs() >= EPSILON {
+        (tmin_numerator / direction, tmax_numerator / direction)
+    } else {
+        (tmin_numerator * f64::INFINITY, tmax_numerator * f64::INFINITY)
+    };
+
+    if tmin > tmax {
+        (tmax, tmin)
+    } else {
+        (tmin, tmax)
+    }
+}
+
+impl PartialEq for Cube {
+
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Shape for Cube {
+
+    fn local_intersect(&self, ray: Ray) -> Intersections {
+
+        let (xtmin, xtmax) = check_axis(ray.origin.x, ray.direction.x);
+        let (ytmin, ytmax) = check_axis(ray.origin.y, ray.direction.y);
+        let (ztmin, ztmax) = check_axis(ray.origin.z, ray.direction.z);
+
+        // the ray is inside the cube only where it is inside all three slabs
+        let tmin = xtmin.max(ytmin).max(ztmin);
+        let tmax = xtmax.min(ytmax).min(ztmax);
+
+        if tmin > tmax {
+            return Intersections::new(vec![]);
+        }
+
+        Intersections::new(vec![Intersection::new(tmin, self.clone_box()), Intersection::new(tmax, self.clone_box())])
+    }
+
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        match other.downcast_ref::<Self>() {
+            Some(s) => self.id == s.id,
+            _ => false
+        }
+    }
+
+    fn local_normal_at(&self, object_point:Point) -> Vector {
+        // the face hit is the one matching the component
+        // with the largest absolute value
+        let maxc = object_point.x.abs().max(object_point.y.abs()).max(object_point.z.abs());
+        if maxc == object_point.x.abs() {
+            Vector::new(object_point.x, 0.0, 0.0)
+        } else if maxc == object_point.y.abs() {
+            Vector::new(0.0, object_point.y, 0.0)
+        } else {
+            Vector::new(0.0, 0.0, object_point.z)
+        }
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+
+    fn material(&self) -> Material {
+        self.material.clone()
+    }
+
+    fn shape_transform(&self) -> &ShapeTransform {
+        &self.transform
+    }
+
+    fn shape_transform_mut(&mut self) -> &mut ShapeTransform {
+        &mut self.transform
+    }
+
+    fn local_bounds(&self) -> Bounds {
+        Bounds::unit()
+    }
+}
@@ -0,0 +1,175 @@
+use crate::bounds::Bounds;
+use crate::get_id;
+use crate::intersection::{Intersection, Intersections};
+use crate::material::Material;
+use crate::matrix::Mat4;
+use crate::ray::Ray;
+use crate::tuple::Point;
+use crate::tuple::Vector;
+use crate::EPSILON;
+
+// `Any` allows us to do dynamic typecasting.
+use std::any::Any;
+use super::shape::Shape;
+use super::transform::ShapeTransform;
+
+
+/// A cylinder of radius 1 centered on the y axis. By default it is infinitely
+/// long, but it can be truncated between [minimum: f64] and [maximum: f64]
+/// (both exclusive) and, when [closed: bool] is true, capped at both ends.
+#[derive(Debug, Clone)]
+pub struct Cylinder {
+    pub id: usize,
+    pub minimum: f64,
+    pub maximum: f64,
+    pub closed: bool,
+    transform: ShapeTransform,
+    material: Material,
+}
+
+impl Cylinder {
+    pub fn new(transform: Option<Mat4>, material: Option<Material>) -> Self {
+        let t = transform.unwrap_or(Mat4::identity());
+        Self {
+            id: get_id(),
+            minimum: f64::NEG_INFINITY,
+            maximum: f64::INFINITY,
+            closed: false,
+            transform: ShapeTransform::new(t),
+            material: material.unwrap_or_default(),
+        }
+    }
+
+    /// This function adds to [xs] the intersections of the ray with the
+    /// end caps, if the cylinder is closed
+    fn intersect_caps(&self, ray: Ray, xs: &mut Vec<Intersection>) {
+        // caps only matter if the cylinder is closed, and might possibly
+        // be intersected by the ray
+        if !self.closed || crate::f64eq(0.0, ray.direction.y) {
+            return;
+        }
+        // check for an intersection with the lower end cap by intersecting
+        // the ray with the plane at y=cyl.minimum
+        let t = (self.minimum - ray.origin.y) / ray.direction.y;
+        if check_cap(ray, t, 1.0) {
+            xs.push(Intersection::new(t, self.clone_box()));
+        }
+        // check for an intersection with the upper end cap by intersecting
+        // the ray with the plane at y=cyl.maximum
+        let t = (self.maximum - ray.origin.y) / ray.direction.y;
+        if check_cap(ray, t, 1.0) {
+            xs.push(Intersection::new(t, self.clone_box()));
+        }
+    }
+}
+
+/// This function checks to see if the intersection at [t] is within
+/// [radius] from the y axis. Shared by the cylinder and the cone caps
+pub(crate) fn check_cap(ray: Ray, t: f64, radius: f64) -> bool {
+    let x = ray.origin.x + t * ray.direction.x;
+    let z = ray.origin.z + t * ray.direction.z;
+    (x.powi(2) + z.powi(2)) <= radius.powi(2) + EPSILON
+}
+
+impl PartialEq for Cylinder {
+
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Shape for Cylinder {
+
+    fn local_intersect(&self, ray: Ray) -> Intersections {
+        let mut xs = vec![];
+
+        let a = ray.direction.x.powi(2) + ray.direction.z.powi(2);
+
+        // a ray parallel to the y axis can't hit the walls
+        if !crate::f64eq(0.0, a) {
+            let b = 2.0 * ray.origin.x * ray.direction.x + 2.0 * ray.origin.z * ray.direction.z;
+            let c = ray.origin.x.powi(2) + ray.origin.z.powi(2) - 1.0;
+            let discriminant = b.powi(2) - 4.0 * a * c;
+
+            // ray does not intersect the cylinder
+            if discriminant < 0.0 {
+                return Intersections::new(vec![]);
+            }
+
+            let mut t0 = (-b - discriminant.sqrt()) / (2.0 * a);
+            let mut t1 = (-b + discriminant.sqrt()) / (2.0 * a);
+            if t0 > t1 {
+                std::mem::swap(&mut t0, &mut t1);
+            }
+
+            // keep only the intersections between the truncation bounds
+            let y0 = ray.origin.y + t0 * ray.direction.y;
+            if self.minimum < y0 && y0 < self.maximum {
+                xs.push(Intersection::new(t0, self.clone_box()));
+            }
+            let y1 = ray.origin.y + t1 * ray.direction.y;
+            if self.minimum < y1 && y1 < self.maximum {
+                xs.push(Intersection::new(t1, self.clone_box()));
+            }
+        }
+
+        self.intersect_caps(ray, &mut xs);
+        Intersections::new(xs)
+    }
+
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        match other.downcast_ref::<Self>() {
+            Some(s) => self.id == s.id,
+            _ => false
+        }
+    }
+
+    fn local_normal_at(&self, object_point:Point) -> Vector {
+        // compute the square of the distance from the y axis
+        let dist = object_point.x.powi(2) + object_point.z.powi(2);
+        if dist < 1.0 && object_point.y >= self.maximum - EPSILON {
+            Vector::new(0.0, 1.0, 0.0)
+        } else if dist < 1.0 && object_point.y <= self.minimum + EPSILON {
+            Vector::new(0.0, -1.0, 0.0)
+        } else {
+            Vector::new(object_point.x, 0.0, object_point.z)
+        }
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+
+    fn material(&self) -> Material {
+        self.material.clone()
+    }
+
+    fn shape_transform(&self) -> &ShapeTransform {
+        &self.transform
+    }
+
+    fn shape_transform_mut(&mut self) -> &mut ShapeTransform {
+        &mut self.transform
+    }
+
+    fn local_bounds(&self) -> Bounds {
+        Bounds::new(
+            Point::new(-1.0, self.minimum, -1.0),
+            Point::new(1.0, self.maximum, 1.0),
+        )
+    }
+}
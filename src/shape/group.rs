@@ -0,0 +1,164 @@
+use crate::bounds::Bounds;
+use crate::bvh::Bvh;
+use crate::get_id;
+use crate::intersection::Intersections;
+use crate::material::Material;
+use crate::matrix::Mat4;
+use crate::ray::Ray;
+use crate::tuple::Point;
+use crate::tuple::Vector;
+
+// `Any` allows us to do dynamic typecasting.
+use std::any::Any;
+use std::sync::OnceLock;
+use super::shape::Shape;
+use super::transform::ShapeTransform;
+
+
+/// A shape that owns a collection of [children: Vec<Box<dyn Shape>>] and
+/// applies its own [transform: Mat4] to all of them, so a whole assembly can
+/// be moved at once. Groups can be nested: every child knows the combined
+/// transform of the groups containing it, which is what lets [normal_at]
+/// find its way from world space down to the child and back. The children
+/// are sorted in a bounding volume hierarchy the first time the group is
+/// intersected, and again after the children change.
+#[derive(Debug, Clone)]
+pub struct Group {
+    pub id: usize,
+    transform: ShapeTransform,
+    material: Material,
+    children: Vec<Box<dyn Shape>>,
+    bvh: OnceLock<Bvh>,
+}
+
+impl Group {
+    pub fn new(transform: Option<Mat4>) -> Self {
+        let t = transform.unwrap_or(Mat4::identity());
+        Self {
+            id: get_id(),
+            transform: ShapeTransform::new(t),
+            material: Material::default(),
+            children: vec![],
+            bvh: OnceLock::new(),
+        }
+    }
+
+    /// Add the given [child] to the group. From now on the
+    /// child is transformed by the group's transform too
+    pub fn add_child(&mut self, mut child: Box<dyn Shape>) {
+        child.set_parent_transform(self.transform.world());
+        self.children.push(child);
+        // the hierarchy is stale, it will be rebuilt on the next intersect
+        self.bvh = OnceLock::new();
+    }
+
+    /// Add all the given [children] to the group
+    pub fn add_children(&mut self, children: Vec<Box<dyn Shape>>) {
+        for child in children {
+            self.add_child(child);
+        }
+    }
+
+    /// Return the shapes contained in the group
+    pub fn children(&self) -> &[Box<dyn Shape>] {
+        &self.children
+    }
+
+    /// Return true if the group has no children
+    pub fn is_empty(&self) -> bool {
+        self.children.is_empty()
+    }
+
+    /// Return the hierarchy of the children, building it if needed
+    pub fn bvh(&self) -> &Bvh {
+        self.bvh.get_or_init(|| Bvh::build(&self.children))
+    }
+
+    /// Push the combined transform of the group down to its children
+    fn update_children(&mut self) {
+        let t = self.transform.world();
+        for child in self.children.iter_mut() {
+            child.set_parent_transform(t);
+        }
+    }
+}
+
+impl PartialEq for Group {
+
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Shape for Group {
+
+    fn local_intersect(&self, ray: Ray) -> Intersections {
+        // each child converts the ray in its own object space
+        Intersections::new(self.bvh().intersect(&self.children, ray))
+    }
+
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn includes(&self, id: usize) -> bool {
+        self.id == id || self.children.iter().any(|c| c.includes(id))
+    }
+
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        match other.downcast_ref::<Self>() {
+            Some(s) => self.id == s.id,
+            _ => false
+        }
+    }
+
+    fn local_normal_at(&self, _pnt:Point) -> Vector {
+        // intersections always refer to the children, a group
+        // has no surface of its own to compute a normal on
+        panic!("Group - normal_at can't be called on a group, only on its children");
+    }
+
+    /// Set the material of the group and of all its children
+    fn set_material(&mut self, material: Material) {
+        for child in self.children.iter_mut() {
+            child.set_material(material.clone());
+        }
+        self.material = material;
+    }
+
+    fn material(&self) -> Material {
+        self.material.clone()
+    }
+
+    fn shape_transform(&self) -> &ShapeTransform {
+        &self.transform
+    }
+
+    fn shape_transform_mut(&mut self) -> &mut ShapeTransform {
+        &mut self.transform
+    }
+
+    fn set_transform(&mut self, transform: Mat4) {
+        self.transform.set(transform);
+        self.update_children();
+    }
+
+    fn set_parent_transform(&mut self, t: Mat4) {
+        self.transform.set_parent(t);
+        self.update_children();
+    }
+
+    fn local_bounds(&self) -> Bounds {
+        self.bvh().bounds()
+    }
+}
@@ -1,6 +1,8 @@
 //use std::time;
 
+use crate::bounds::Bounds;
 use crate::get_id; 
+use crate::EPSILON;
 use crate::intersection::{Intersection, Intersections}; 
 use crate::material::Material; 
 use crate::matrix::Mat4; 
@@ -11,16 +13,15 @@ use crate::tuple::Vector;
 // `Any` allows us to do dynamic typecasting.
 use std::any::Any;
 use super::shape::Shape;
+use super::transform::ShapeTransform;
 
 
 /// A shape that contain an [id: usize] a defaul idenityt [transform: Mat4] and
 /// a default [material: Material]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub struct Plane {
     pub id: usize,
-    transform: Mat4,
-    inverse_transform: Mat4,
-    transpose_inverse_transform: Mat4,
+    transform: ShapeTransform,
     material: Material,
 }
 
@@ -29,10 +30,8 @@ impl Plane {
         let t = transform.unwrap_or(Mat4::identity());
         Self {
             id: get_id(),
-            transform: t, 
-            inverse_transform: t.inv(),
-            transpose_inverse_transform: t.inv().transpose(),
-            material: material.unwrap_or( Material::default()),
+            transform: ShapeTransform::new(t),
+            material: material.unwrap_or_default(),
         }
     }
 }
@@ -47,13 +46,14 @@ impl PartialEq for Plane {
 
 impl Shape for Plane {
     
-    fn intersect(&self, ray: Ray) -> Intersections {
-        if crate::f64eq(0.0, ray.direction.y) {
+    fn local_intersect(&self, ray: Ray) -> Intersections {
+        // a ray parallel to the plane (or coplanar with it) never hits it
+        if ray.direction.y.abs() < EPSILON {
             return Intersections::new(vec![]);
         }
         let t = -ray.origin.y / ray.direction.y;
 
-        Intersections::new(vec![Intersection::new(t, Box::new(self.clone()))])
+        Intersections::new(vec![Intersection::new(t, self.clone_box())])
     }   
 
     fn id(&self) -> usize {
@@ -78,8 +78,9 @@ impl Shape for Plane {
         } 
     }
     
-    fn normal_at(&self, _pnt:Point) -> Vector {
-        Vector::new(0.0, 0.1, 0.0)
+    fn local_normal_at(&self, _pnt:Point) -> Vector {
+        // in object space the plane is xz, its normal is the same everywhere
+        Vector::new(0.0, 1.0, 0.0)
     }
 
     fn set_material(&mut self, material: Material) {
@@ -90,13 +91,19 @@ impl Shape for Plane {
         self.material.clone()
     }
     
-    fn set_transform(&mut self, transform: Mat4) {
-        self.transform = transform;
-        self.inverse_transform = transform.inv();
-        self.transpose_inverse_transform = transform.inv().transpose();
+    fn shape_transform(&self) -> &ShapeTransform {
+        &self.transform
     }
 
-    fn transform(&self) -> Mat4 {
-        self.transform
+    fn shape_transform_mut(&mut self) -> &mut ShapeTransform {
+        &mut self.transform
+    }
+
+    fn local_bounds(&self) -> Bounds {
+        // the plane is infinitely wide and infinitely thin
+        Bounds::new(
+            Point::new(f64::NEG_INFINITY, 0.0, f64::NEG_INFINITY),
+            Point::new(f64::INFINITY, 0.0, f64::INFINITY),
+        )
     }
 }
@@ -1,22 +1,45 @@
 //use crate::material::Material;
 
+use crate::bounds::Bounds;
 use crate::material::Material;
 use crate::matrix::Mat4;
 use crate::tuple::{Point, Vector};
-use crate::{intersection::Intersections, ray::Ray};
+use crate::{intersection::{Intersection, Intersections}, ray::Ray};
 use std::fmt;
 // `Any` allows us to do dynamic typecasting.
 use std::any::Any;
+use super::transform::ShapeTransform;
 
 
-pub trait Shape : fmt::Debug {
-    /// It return any Intersections that occured 
+/// Every shape lives in its own object space, where it has a simple
+/// definition (the unit sphere, the xz plane, ...). A shape only has to say
+/// how a ray in object space hits it ([local_intersect]) and what its normal
+/// is at a point in object space ([local_normal_at]): the default methods of
+/// the trait take care of moving rays, points and normals between world space
+/// and object space, through all the groups containing the shape.
+/// The camera renders the world on several threads at once, so shapes
+/// (and the materials, patterns and lights of the world) must be [Send + Sync]
+pub trait Shape : fmt::Debug + Send + Sync {
+    /// It return any Intersections that occured
     /// between the shape and the ray provided as
     /// argument
-    fn intersect(&self, ray: Ray) -> Intersections;
+    fn intersect(&self, ray: Ray) -> Intersections {
+        // convert the ray from the parent space to object space
+        self.local_intersect(ray.transform(&self.shape_transform().inverse()))
+    }
+
+    /// Return the intersections between the shape and
+    /// the given ray, already converted in object space
+    fn local_intersect(&self, ray: Ray) -> Intersections;
 
     fn id(&self) -> usize;
 
+    /// Return true if the shape is, or contains, the shape with the given
+    /// [id]. Shapes with children (groups, csg) look inside them too
+    fn includes(&self, id: usize) -> bool {
+        self.id() == id
+    }
+
     // I need this because can't implement a clone for a trait object
     fn clone_box(&self) -> Box<dyn Shape>;
 
@@ -28,7 +51,23 @@ pub trait Shape : fmt::Debug {
 
     /// Return the normal vector from the shape
     /// for the given point
-    fn normal_at(&self, p:Point) -> Vector;
+    fn normal_at(&self, p:Point) -> Vector {
+        let local_point = self.world_to_object(p);
+        let local_normal = self.local_normal_at(local_point);
+        self.normal_to_world(local_normal)
+    }
+
+    /// Return the normal vector of the shape for
+    /// the given point, both in object space
+    fn local_normal_at(&self, p: Point) -> Vector;
+
+    /// Return the normal vector from the shape for the given point
+    /// using the [hit: Intersection] that found it. Shapes that
+    /// interpolate their normals across the surface (smooth triangles)
+    /// read the hit's [u, v] here, all the others simply use [normal_at]
+    fn normal_at_hit(&self, p: Point, _hit: &Intersection) -> Vector {
+        self.normal_at(p)
+    }
 
     /// Return shape's material
     fn material(&self) ->Material;
@@ -36,10 +75,50 @@ pub trait Shape : fmt::Debug {
     /// Set shape's material
     fn set_material(&mut self, material: Material);
 
+    /// Return the matrices used to move between
+    /// object space, parent space and world space
+    fn shape_transform(&self) -> &ShapeTransform;
+    fn shape_transform_mut(&mut self) -> &mut ShapeTransform;
+
     /// Return Transform
-    fn transform(&self) -> Mat4;
+    fn transform(&self) -> Mat4 {
+        self.shape_transform().matrix()
+    }
     /// Set shape's transform
-    fn set_transform(&mut self, t: Mat4);
+    fn set_transform(&mut self, t: Mat4) {
+        self.shape_transform_mut().set(t);
+    }
+
+    /// Return the combined transform of all the groups
+    /// containing the shape (identity if it has no parent)
+    fn parent_transform(&self) -> Mat4 {
+        self.shape_transform().parent()
+    }
+    /// Set the combined transform of all the groups containing the
+    /// shape. Groups call it on their children when they change
+    fn set_parent_transform(&mut self, t: Mat4) {
+        self.shape_transform_mut().set_parent(t);
+    }
+
+    /// Convert the given point from world space to object space,
+    /// going through all the groups containing the shape
+    fn world_to_object(&self, p: Point) -> Point {
+        self.shape_transform().world_to_object(p)
+    }
+    /// Convert the given normal from object space to world space,
+    /// going through all the groups containing the shape
+    fn normal_to_world(&self, n: Vector) -> Vector {
+        self.shape_transform().normal_to_world(n)
+    }
+
+    /// Return the axis-aligned box containing the shape, in the
+    /// space of its parent (the world, or the group containing it)
+    fn bounds(&self) -> Bounds {
+        self.local_bounds().transform(&self.transform())
+    }
+
+    /// Return the axis-aligned box containing the shape in object space
+    fn local_bounds(&self) -> Bounds;
 }
 
 impl Clone for Box<dyn Shape> {
@@ -53,5 +132,3 @@ impl PartialEq for Box<dyn Shape> {
         self.eq_box(other.as_any())
     }
 }
-
-
@@ -0,0 +1,126 @@
+use crate::bounds::Bounds;
+use crate::get_id;
+use crate::intersection::{Intersection, Intersections};
+use crate::material::Material;
+use crate::matrix::Mat4;
+use crate::ray::Ray;
+use crate::tuple::Point;
+use crate::tuple::Vector;
+
+// `Any` allows us to do dynamic typecasting.
+use std::any::Any;
+use super::shape::Shape;
+use super::transform::ShapeTransform;
+use super::triangle::intersect_triangle;
+
+
+/// A triangle with a normal on each vertex [n1, n2, n3: Vector]. The normal
+/// at any point of the surface is interpolated from the vertex normals using
+/// the [u, v] coordinates of the hit, which makes a mesh of smooth triangles
+/// look curved instead of faceted
+#[derive(Debug, Clone)]
+pub struct SmoothTriangle {
+    pub id: usize,
+    pub p1: Point,
+    pub p2: Point,
+    pub p3: Point,
+    pub n1: Vector,
+    pub n2: Vector,
+    pub n3: Vector,
+    pub e1: Vector,
+    pub e2: Vector,
+    transform: ShapeTransform,
+    material: Material,
+}
+
+impl SmoothTriangle {
+    pub fn new(p1: Point, p2: Point, p3: Point, n1: Vector, n2: Vector, n3: Vector) -> Self {
+        let t = Mat4::identity();
+        Self {
+            id: get_id(),
+            p1,
+            p2,
+            p3,
+            n1,
+            n2,
+            n3,
+            e1: p2 - p1,
+            e2: p3 - p1,
+            transform: ShapeTransform::new(t),
+            material: Material::default(),
+        }
+    }
+}
+
+impl PartialEq for SmoothTriangle {
+
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Shape for SmoothTriangle {
+
+    fn local_intersect(&self, ray: Ray) -> Intersections {
+        match intersect_triangle(ray, self.p1, self.e1, self.e2) {
+            Some((t, u, v)) => Intersections::new(vec![Intersection::new_with_uv(t, self.clone_box(), u, v)]),
+            None => Intersections::new(vec![]),
+        }
+    }
+
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        match other.downcast_ref::<Self>() {
+            Some(s) => self.id == s.id,
+            _ => false
+        }
+    }
+
+    fn local_normal_at(&self, _pnt:Point) -> Vector {
+        // without a hit there are no [u, v] to interpolate
+        // with, so fall back on the flat normal of the face
+        self.e2.cross(&self.e1)
+    }
+
+    fn normal_at_hit(&self, _pnt: Point, hit: &Intersection) -> Vector {
+        let object_normal = self.n2 * hit.u + self.n3 * hit.v + self.n1 * (1.0 - hit.u - hit.v);
+        self.normal_to_world(object_normal)
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+
+    fn material(&self) -> Material {
+        self.material.clone()
+    }
+
+    fn shape_transform(&self) -> &ShapeTransform {
+        &self.transform
+    }
+
+    fn shape_transform_mut(&mut self) -> &mut ShapeTransform {
+        &mut self.transform
+    }
+
+    fn local_bounds(&self) -> Bounds {
+        let mut b = Bounds::empty();
+        b.add_point(self.p1);
+        b.add_point(self.p2);
+        b.add_point(self.p3);
+        b
+    }
+}
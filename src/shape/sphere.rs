@@ -1,5 +1,6 @@
 //use std::time;
 
+use crate::bounds::Bounds;
 use crate::get_id; 
 use crate::intersection::{Intersection, Intersections}; 
 use crate::material::Material; 
@@ -11,16 +12,15 @@ use crate::tuple::Vector;
 // `Any` allows us to do dynamic typecasting.
 use std::any::Any;
 use super::shape::Shape;
+use super::transform::ShapeTransform;
 
 
 /// A shape that contain an [id: usize] a defaul idenityt [transform: Mat4] and
 /// a default [material: Material]
-#[derive(Debug, Copy, Clone)]
+#[derive(Debug, Clone)]
 pub struct Sphere {
     pub id: usize,
-    transform: Mat4,
-    inverse_transform: Mat4,
-    transpose_inverse_transform: Mat4,
+    transform: ShapeTransform,
     material: Material,
 }
 
@@ -29,10 +29,8 @@ impl Sphere {
         let t = transform.unwrap_or(Mat4::identity());
         Self {
             id: get_id(),
-            transform: t, 
-            inverse_transform: t.inv(),
-            transpose_inverse_transform: t.inv().transpose(),
-            material: material.unwrap_or( Material::default()),
+            transform: ShapeTransform::new(t),
+            material: material.unwrap_or_default(),
         }
     }
 }
@@ -47,15 +45,13 @@ impl PartialEq for Sphere {
 
 impl Shape for Sphere {
     
-    fn intersect(&self, ray: Ray) -> Intersections {
-        // convert the ray from world space to object space
-        let ray = ray.transform(&self.inverse_transform);
+    fn local_intersect(&self, ray: Ray) -> Intersections {
         let sphere_to_ray = ray.origin - Point::new(0.0, 0.0, 0.0);
         //println!("{} elpased.", start.elapsed().as_micros());
 
         let a = ray.direction.dot(&ray.direction);
         
-        let b = 2.0 * ray.direction.dot(&sphere_to_ray.into());
+        let b = 2.0 * ray.direction.dot(&sphere_to_ray);
 
         let c = sphere_to_ray.dot(&sphere_to_ray) - 1.0 ;
         
@@ -68,7 +64,7 @@ impl Shape for Sphere {
         let t1 = (-b - discriminant.sqrt()) / (2.0 * a);
         let t2 = (-b + discriminant.sqrt()) / (2.0 * a);
   
-        Intersections::new(vec![Intersection { t: t1, object: Box::new(*self)} , Intersection { t: t2, object: Box::new(*self)}])
+        Intersections::new(vec![Intersection::new(t1, self.clone_box()), Intersection::new(t2, self.clone_box())])
     }   
 
     fn id(&self) -> usize {
@@ -93,15 +89,9 @@ impl Shape for Sphere {
         } 
     }
     
-    fn normal_at(&self, pnt:Point) -> Vector {
-        // convert the point from world space to object space
-        let object_point =  self.inverse_transform * pnt;
-        // Now I can calculate the object normal 
-        let object_normal = object_point - Point::new(0., 0., 0.);
-        // Now transform the object_normal in world space
-        let mut world_normal =  self.transpose_inverse_transform * object_normal;
-        world_normal.w = 0.0;
-        world_normal.normalize()
+    fn local_normal_at(&self, object_point:Point) -> Vector {
+        // on the unit sphere the normal goes from the center to the point
+        object_point - Point::new(0., 0., 0.)
     }
 
     fn set_material(&mut self, material: Material) {
@@ -112,13 +102,16 @@ impl Shape for Sphere {
         self.material.clone()
     }
     
-    fn set_transform(&mut self, transform: Mat4) {
-        self.transform = transform;
-        self.inverse_transform = transform.inv();
-        self.transpose_inverse_transform = transform.inv().transpose();
+    fn shape_transform(&self) -> &ShapeTransform {
+        &self.transform
     }
 
-    fn transform(&self) -> Mat4 {
-        self.transform
+    fn shape_transform_mut(&mut self) -> &mut ShapeTransform {
+        &mut self.transform
+    }
+
+    fn local_bounds(&self) -> Bounds {
+        // the unit sphere fits in the -1..+1 box
+        Bounds::unit()
     }
 }
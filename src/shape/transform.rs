@@ -0,0 +1,82 @@
+use crate::matrix::Mat4;
+use crate::tuple::{Point, Vector};
+
+/// All the matrices a shape needs to move between spaces. Besides its own
+/// [transform: Mat4] (from object space to the parent space) a shape keeps
+/// the combined [parent: Mat4] transform of the groups containing it, and
+/// caches the inverses that intersecting and shading use on every ray
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ShapeTransform {
+    transform: Mat4,
+    inverse: Mat4,
+    parent: Mat4,
+    world_inverse: Mat4,
+    world_transpose_inverse: Mat4,
+}
+
+impl ShapeTransform {
+    pub fn new(transform: Mat4) -> Self {
+        let inverse = transform.inv();
+        Self {
+            transform,
+            inverse,
+            parent: Mat4::identity(),
+            world_inverse: inverse,
+            world_transpose_inverse: inverse.transpose(),
+        }
+    }
+
+    /// Return the transform from object space to the parent space
+    pub fn matrix(&self) -> Mat4 {
+        self.transform
+    }
+
+    /// Return the transform from the parent space to object space
+    pub fn inverse(&self) -> Mat4 {
+        self.inverse
+    }
+
+    /// Return the combined transform of the groups containing the shape
+    pub fn parent(&self) -> Mat4 {
+        self.parent
+    }
+
+    /// Return the transform from object space to world space
+    pub fn world(&self) -> Mat4 {
+        self.parent * self.transform
+    }
+
+    pub fn set(&mut self, transform: Mat4) {
+        self.transform = transform;
+        self.inverse = transform.inv();
+        self.update_world();
+    }
+
+    pub fn set_parent(&mut self, parent: Mat4) {
+        self.parent = parent;
+        self.update_world();
+    }
+
+    /// Convert the given point from world space to object space
+    pub fn world_to_object(&self, p: Point) -> Point {
+        self.world_inverse * p
+    }
+
+    /// Convert the given normal from object space to world space
+    pub fn normal_to_world(&self, n: Vector) -> Vector {
+        let mut world_normal = self.world_transpose_inverse * n;
+        world_normal.w = 0.0;
+        world_normal.normalize()
+    }
+
+    fn update_world(&mut self) {
+        self.world_inverse = self.world().inv();
+        self.world_transpose_inverse = self.world_inverse.transpose();
+    }
+}
+
+impl Default for ShapeTransform {
+    fn default() -> Self {
+        Self::new(Mat4::identity())
+    }
+}
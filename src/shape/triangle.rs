@@ -0,0 +1,146 @@
+use crate::bounds::Bounds;
+use crate::get_id;
+use crate::intersection::{Intersection, Intersections};
+use crate::material::Material;
+use crate::matrix::Mat4;
+use crate::ray::Ray;
+use crate::tuple::Point;
+use crate::tuple::Vector;
+use crate::EPSILON;
+
+// `Any` allows us to do dynamic typecasting.
+use std::any::Any;
+use super::shape::Shape;
+use super::transform::ShapeTransform;
+
+
+/// A triangle defined by its three vertices [p1, p2, p3: Point]. The two
+/// edge vectors [e1, e2: Vector] and the flat [normal: Vector] are
+/// precomputed when the triangle is created, as they never change
+#[derive(Debug, Clone)]
+pub struct Triangle {
+    pub id: usize,
+    pub p1: Point,
+    pub p2: Point,
+    pub p3: Point,
+    pub e1: Vector,
+    pub e2: Vector,
+    pub normal: Vector,
+    transform: ShapeTransform,
+    material: Material,
+}
+
+impl Triangle {
+    pub fn new(p1: Point, p2: Point, p3: Point) -> Self {
+        let e1 = p2 - p1;
+        let e2 = p3 - p1;
+        let t = Mat4::identity();
+        Self {
+            id: get_id(),
+            p1,
+            p2,
+            p3,
+            e1,
+            e2,
+            normal: e2.cross(&e1).normalize(),
+            transform: ShapeTransform::new(t),
+            material: Material::default(),
+        }
+    }
+}
+
+/// This function implements the Möller–Trumbore algorithm: it intersects
+/// the ray (in object space) with the triangle [p1] [e1] [e2] and returns
+/// the [t] of the intersection together with its barycentric [u, v]
+/// coordinates, or [None] if the ray misses the triangle
+pub(crate) fn intersect_triangle(ray: Ray, p1: Point, e1: Vector, e2: Vector) -> Option<(f64, f64, f64)> {
+    let dir_cross_e2 = ray.direction.cross(&e2);
+    let det = e1.dot(&dir_cross_e2);
+    // the ray is parallel to the triangle's plane
+    if det.abs() < EPSILON {
+        return None;
+    }
+
+    let f = 1.0 / det;
+    let p1_to_origin = ray.origin - p1;
+    let u = f * p1_to_origin.dot(&dir_cross_e2);
+    if !(0.0..=1.0).contains(&u) {
+        return None;
+    }
+
+    let origin_cross_e1 = p1_to_origin.cross(&e1);
+    let v = f * ray.direction.dot(&origin_cross_e1);
+    if v < 0.0 || (u + v) > 1.0 {
+        return None;
+    }
+
+    let t = f * e2.dot(&origin_cross_e1);
+    Some((t, u, v))
+}
+
+impl PartialEq for Triangle {
+
+    fn eq(&self, other: &Self) -> bool {
+        self.id() == other.id()
+    }
+}
+
+impl Shape for Triangle {
+
+    fn local_intersect(&self, ray: Ray) -> Intersections {
+        match intersect_triangle(ray, self.p1, self.e1, self.e2) {
+            Some((t, u, v)) => Intersections::new(vec![Intersection::new_with_uv(t, self.clone_box(), u, v)]),
+            None => Intersections::new(vec![]),
+        }
+    }
+
+    fn id(&self) -> usize {
+        self.id
+    }
+
+    fn clone_box(&self) -> Box<dyn Shape> {
+        Box::new(self.clone())
+    }
+
+    // An &Any can be cast to a reference to a concrete type.
+    fn as_any(&self) -> &dyn Any{
+        self
+    }
+
+    // Perform the test.
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        match other.downcast_ref::<Self>() {
+            Some(s) => self.id == s.id,
+            _ => false
+        }
+    }
+
+    fn local_normal_at(&self, _pnt:Point) -> Vector {
+        // the normal is the same everywhere on the triangle
+        self.normal
+    }
+
+    fn set_material(&mut self, material: Material) {
+        self.material = material;
+    }
+
+    fn material(&self) -> Material {
+        self.material.clone()
+    }
+
+    fn shape_transform(&self) -> &ShapeTransform {
+        &self.transform
+    }
+
+    fn shape_transform_mut(&mut self) -> &mut ShapeTransform {
+        &mut self.transform
+    }
+
+    fn local_bounds(&self) -> Bounds {
+        let mut b = Bounds::empty();
+        b.add_point(self.p1);
+        b.add_point(self.p2);
+        b.add_point(self.p3);
+        b
+    }
+}
@@ -0,0 +1,77 @@
+use crate::color::Color;
+
+/// How the colors above 1 are squeezed into the 0..1 range of an image.
+/// [Clamp] cuts them off, [Reinhard] maps every channel c to c / (1 + c),
+/// [ExtendedReinhard] does the same but maps the given white point to 1,
+/// so that the colors below it are not washed out, and [Aces] follows the
+/// filmic curve of the Academy Color Encoding System (Krzysztof Narkowicz's
+/// fit), with more contrast in the mid tones
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub enum ToneOperator {
+    #[default]
+    Clamp,
+    Reinhard,
+    ExtendedReinhard(f64),
+    Aces,
+}
+
+impl ToneOperator {
+    /// Return the tone mapped value of the channel [c], between 0 and 1
+    pub fn map(&self, c: f64) -> f64 {
+        let c = c.max(0.0);
+        let mapped = match *self {
+            ToneOperator::Clamp => c,
+            ToneOperator::Reinhard => c / (1.0 + c),
+            ToneOperator::ExtendedReinhard(white) => c * (1.0 + c / (white * white)) / (1.0 + c),
+            ToneOperator::Aces => (c * (2.51 * c + 0.03)) / (c * (2.43 * c + 0.59) + 0.14),
+        };
+        mapped.clamp(0.0, 1.0)
+    }
+
+    /// Parse the name of an operator: clamp, reinhard, aces or
+    /// extended-reinhard:WHITE, with the white point after the colon
+    pub fn parse(name: &str) -> Result<Self, String> {
+        match name.split_once(':') {
+            None => match name {
+                "clamp" => Ok(ToneOperator::Clamp),
+                "reinhard" => Ok(ToneOperator::Reinhard),
+                "aces" => Ok(ToneOperator::Aces),
+                "extended-reinhard" => Err("extended-reinhard needs a white point, like extended-reinhard:4".to_string()),
+                _ => Err(format!("unknown tone operator `{}`", name)),
+            },
+            Some(("extended-reinhard", white)) => match white.parse::<f64>() {
+                Ok(white) if white > 0.0 => Ok(ToneOperator::ExtendedReinhard(white)),
+                _ => Err(format!("expected a positive white point, found `{}`", white)),
+            },
+            Some(_) => Err(format!("unknown tone operator `{}`", name)),
+        }
+    }
+}
+
+/// The post process between the rendered canvas and the bytes of an
+/// image: the colors are scaled by 2^[exposure: f64] (in stops, so +1
+/// doubles the light) and then passed through the tone [operator].
+/// The result is still linear, [Canvas::save](crate::canvas::Canvas::save)
+/// encodes it as sRGB for the formats that expect it
+#[derive(Debug, Copy, Clone, PartialEq, Default)]
+pub struct ToneMap {
+    pub exposure: f64,
+    pub operator: ToneOperator,
+}
+
+impl ToneMap {
+    pub fn new(exposure: f64, operator: ToneOperator) -> Self {
+        Self { exposure, operator }
+    }
+
+    pub fn apply(&self, color: Color) -> Color {
+        let c = self.expose(color);
+        Color::new(self.operator.map(c.r), self.operator.map(c.g), self.operator.map(c.b))
+    }
+
+    /// Return the [color] scaled by the exposure alone, without the
+    /// operator, so the values above 1 are kept
+    pub fn expose(&self, color: Color) -> Color {
+        color * 2f64.powf(self.exposure)
+    }
+}
@@ -1,31 +1,98 @@
+use crate::bvh::Bvh;
 use crate::comps::Comps;
 use crate::intersection::Intersections;
-use crate::light::Light;
+use crate::light::{Light, LightSample, LightSource};
 use crate::material::Material;
 use crate::matrix::mat4::scale;
 use crate::ray::Ray;
 use crate::shape::{Shape, Sphere};
-use crate::color::{BLACK, Color};
+use crate::color::{BLACK, Color, WHITE};
 use crate::tuple::*;
+use std::sync::OnceLock;
 
+/// How many times a ray can bounce between reflective and
+/// transparent surfaces before the world stops following it
+pub const MAX_DEPTH: usize = 5;
+
+/// The world holds the [lights: Vec<Box<dyn LightSource>>] and the [objects] of the scene.
+/// To avoid testing every object for every ray, the objects are sorted in a
+/// bounding volume hierarchy, built by the first ray that needs it, like the
+/// one of a [Group](crate::shape::Group). [add_object] and [set_object] drop
+/// it, so the next ray builds it again with the new objects. After changing
+/// [objects] directly call [build_bvh]: until then the objects pushed since
+/// the last build are still found, the world just tests all of them.
+/// Rays bounce between reflective and transparent surfaces at most
+/// [max_depth: usize] times, so two facing mirrors can't recurse forever.
+/// The [ambient: Option<Color>] light is the light bounced around the scene, that
+/// reaches every surface once however many lights there are. Left to None it
+/// follows the lights, see [ambient_light].
 #[derive(Debug, Clone)]
 pub struct World {
-    pub light: Option<Light>,
+    pub lights: Vec<Box<dyn LightSource>>,
     pub objects: Vec<Box<dyn Shape>>,
+    pub max_depth: usize,
+    pub ambient: Option<Color>,
+    bvh: OnceLock<Bvh>,
 }
 
 impl World {
-    pub fn new(light: Option<Light>, objects: Option< Vec<Box<dyn Shape>>>) -> Self {
+    pub fn new(lights: Option<Vec<Box<dyn LightSource>>>, objects: Option< Vec<Box<dyn Shape>>>) -> Self {
     
         Self {
-            light,
-            objects: objects.unwrap_or(vec![]),
+            lights: lights.unwrap_or_default(),
+            objects: objects.unwrap_or_default(),
+            max_depth: MAX_DEPTH,
+            ambient: None,
+            bvh: OnceLock::new(),
+        }
+    }
+
+    /// Return the ambient light of the world: the [ambient] color if set,
+    /// otherwise the average intensity of the lights, so a single light gives
+    /// the same ambient as [Material::lighting]. Without lights it is white
+    pub fn ambient_light(&self) -> Color {
+        if let Some(ambient) = self.ambient {
+            return ambient;
+        }
+        if self.lights.is_empty() {
+            return WHITE;
         }
+        let sum = self.lights.iter().fold(BLACK, |sum, light| sum + light.intensity());
+        sum * (1.0 / self.lights.len() as f64)
+    }
+
+    /// Sort the objects of the world in a bounding volume hierarchy
+    pub fn build_bvh(&mut self) {
+        self.bvh = OnceLock::from(Bvh::build(&self.objects));
+    }
+
+    /// Return the bounding volume hierarchy of the objects, built on first use
+    pub fn bvh(&self) -> &Bvh {
+        self.bvh.get_or_init(|| Bvh::build(&self.objects))
+    }
+
+    /// Add the [object] to the world, the hierarchy
+    /// is built again by the next ray
+    pub fn add_object(&mut self, object: Box<dyn Shape>) {
+        self.objects.push(object);
+        self.bvh = OnceLock::new();
+    }
+
+    /// Replace the object at [index] with [object], for instance the same
+    /// shape with a new transform or material. Panics if [index] is out of bounds
+    pub fn set_object(&mut self, index: usize, object: Box<dyn Shape>) {
+        self.objects[index] = object;
+        self.bvh = OnceLock::new();
     }
 
     /// Function iterate over all of the objects that have been added to the world, 
-    /// intersecting each of them with the given ray, and returnig the collection
+    /// intersecting each of them with the given ray, and returnig the collection.
+    /// Only the objects whose bounding boxes are crossed by the ray are tested
     pub fn intersect(&self, ray: Ray) -> Intersections {
+        let bvh = self.bvh();
+        if bvh.len() == self.objects.len() {
+            return Intersections::new(bvh.intersect(&self.objects, ray));
+        }
         let vec_intersections = self.objects.iter().map(|obj| obj.intersect(ray)).collect::<Vec<Intersections>>();
         let mut xs = vec![];
         for intersections in vec_intersections {
@@ -37,45 +104,131 @@ impl World {
     }
 
     /// This function return the color at the intersection encapsulated
-    /// by the given [c: Comps] with the world
+    /// by the given [c: Comps] with the world, following the reflected
+    /// and refracted rays at most [max_depth] times. The surface gets the
+    /// [ambient] light once, then every light adds its own diffuse and
+    /// specular contribution, and casts its own shadows. Without lights
+    /// only the ambient color of the surface is left
     pub fn shade_hit(&self, c: Comps) -> Color {
-        //c.object.material().lighting(self.light.unwrap(), c.point, c.eyev, c.normalv, false)
-        c.object.material().lighting(
-            self.light.unwrap(), 
-            c.point, 
-            c.eyev, 
-            c.normalv, 
-            self.is_shadowed(c.over_point))
+        self.shade_hit_depth(c, self.max_depth)
+    }
+
+    /// Same as [shade_hit], but the reflected and refracted rays
+    /// are followed at most [remaining] more times
+    pub fn shade_hit_depth(&self, c: Comps, remaining: usize) -> Color {
+        let material = c.object.material();
+        let ambient = material.ambient_at(c.object.as_ref(), c.point, self.ambient_light());
+        let surface = self.lights.iter().fold(ambient, |color, light| {
+            // the same samples of the light for the shadows and the shading
+            let samples = light.attenuated_samples(c.point);
+            color + material.direct_lighting(
+                c.object.as_ref(),
+                &samples,
+                c.point, 
+                c.eyev, 
+                c.normalv, 
+                self.visible_fraction(c.over_point, &samples))
+        });
+        let reflected = self.reflected_color(&c, remaining);
+        let refracted = self.refracted_color(&c, remaining);
+
+        if material.reflective > 0.0 && material.transparency > 0.0 {
+            // blend the two by how much light the surface reflects
+            let reflectance = c.schlick();
+            return surface + reflected * reflectance + refracted * (1.0 - reflectance);
+        }
+        surface + reflected + refracted
     }
 
     /// This function intersect the world with the given ray 
     /// and then return the color at the resulting intersection.
     pub fn color_at(&self, r: Ray) -> Color {
+        self.color_at_depth(r, self.max_depth)
+    }
+
+    /// Same as [color_at], but the reflected and refracted rays
+    /// are followed at most [remaining] more times
+    pub fn color_at_depth(&self, r: Ray, remaining: usize) -> Color {
         let xs = self.intersect(r);        
         if let Some(hit) = xs.hit() {
-            self.shade_hit(hit.prepare_computation(r))
+            self.shade_hit_depth(hit.prepare_computation_with(r, &xs), remaining)
         } else {
             BLACK
         }
     }
 
+    /// This function return the color the surface in [c: Comps] reflects,
+    /// scaled by how reflective its material is. Once [remaining] reaches
+    /// zero the reflected ray is not followed anymore and black is returned
+    pub fn reflected_color(&self, c: &Comps, remaining: usize) -> Color {
+        let reflective = c.object.material().reflective;
+        if remaining == 0 || reflective == 0.0 {
+            return BLACK;
+        }
+        // start from over_point, or the ray would hit the surface itself
+        let reflect_ray = Ray::new(c.over_point, c.reflectv);
+        self.color_at_depth(reflect_ray, remaining - 1) * reflective
+    }
+
+    /// This function return the color seen through the surface in [c: Comps],
+    /// scaled by how transparent its material is. Opaque surfaces, total
+    /// internal reflection and [remaining] reaching zero all return black
+    pub fn refracted_color(&self, c: &Comps, remaining: usize) -> Color {
+        let transparency = c.object.material().transparency;
+        if remaining == 0 || transparency == 0.0 {
+            return BLACK;
+        }
+        // find the ratio of first index of refraction to the second
+        // and cos(theta_i), then sin(theta_t)^2 via trigonometric identity
+        let n_ratio = c.n1 / c.n2;
+        let cos_i = c.eyev.dot(&c.normalv);
+        let sin2_t = n_ratio.powi(2) * (1.0 - cos_i.powi(2));
+        if sin2_t > 1.0 {
+            // total internal reflection
+            return BLACK;
+        }
+        // compute the direction of the refracted ray, which
+        // starts from under_point to get past the surface
+        let cos_t = (1.0 - sin2_t).sqrt();
+        let direction = c.normalv * (n_ratio * cos_i - cos_t) - c.eyev * n_ratio;
+        let refract_ray = Ray::new(c.under_point, direction);
+        self.color_at_depth(refract_ray, remaining - 1) * transparency
+    }
+
+    /// This function return the fraction of the given [light: &dyn LightSource] that
+    /// reaches the point: 1 when nothing is in the way, 0 when the point is
+    /// in shadow, and the fraction of the sampled points on the light that
+    /// the point can see for area lights
+    pub fn intensity_at(&self, light: &dyn LightSource, p: Point) -> f64 {
+        self.visible_fraction(p, &light.samples(p))
+    }
+
+    /// This function return the fraction of the [samples: &[LightSample]]
+    /// of a light that are not blocked by anything, as seen from [p: Point]
+    pub fn visible_fraction(&self, p: Point, samples: &[LightSample]) -> f64 {
+        if samples.is_empty() {
+            return 0.0;
+        }
+        let visible = samples
+            .iter()
+            .filter(|sample| !self.is_blocked(p, sample.direction, sample.distance))
+            .count();
+        visible as f64 / samples.len() as f64
+    }
+
     /// This function return true if somethin intersect
-    /// between the point and the light source
-    pub fn is_shadowed(&self, p: Point) -> bool {
+    /// between the point and the [light_position: Point]
+    pub fn is_shadowed(&self, light_position: Point, p: Point) -> bool {
         // Measure the distance from point to the light source 
-        let v;
-        if let Some(light) = self.light {
-            v = light.position - p;
-        } else {
-            panic!("There are no light defined for the world [{:?}]", self);
-        }
-        let distance = v.magnitude();
-        
-        // Create a ray from point toward the light source 
-        // by normalizing the previus vector
-        let direction = v.normalize();
+        let v = light_position - p;
+        self.is_blocked(p, v.normalize(), v.magnitude())
+    }
 
-        // Intersect the world with that ray
+    /// This function return true if something intersect the ray leaving
+    /// [p: Point] along the normalized [direction: Vector] before
+    /// [distance: f64]. Lights infinitely far away use an infinite distance
+    fn is_blocked(&self, p: Point, direction: Vector, distance: f64) -> bool {
+        // Intersect the world with a ray from point toward the light source 
         let r = Ray::new(p, direction);
         let intersections = self.intersect(r);
   
@@ -104,12 +257,12 @@ impl Default for World {
         let s1 = Box::new(Sphere::new(None, Some(mtrl)));
         let s2 = Box::new(Sphere::new(Some(trm), None));
         
-        World { 
-            light: Some(Light::new(
+        World::new( 
+            Some(vec![Box::new(Light::new(
                 Point::new(-10., 10., -10.), 
-                Color::new(1., 1., 1.))
-            ), 
-            objects: vec![s1, s2],
-        }
+                Color::new(1., 1., 1.)))
+            ]), 
+            Some(vec![s1, s2]),
+        )
     }
 }
@@ -0,0 +1,133 @@
+use std::f64::consts::{PI, SQRT_2};
+
+use raytracer::bounds::Bounds;
+use raytracer::matrix::mat4::{rotate_x, rotate_y, scale, translate};
+use raytracer::ray::Ray;
+use raytracer::shape::{Cone, Cube, Cylinder, Group, Plane, Shape, Sphere, Triangle};
+use raytracer::tuple::{Point, Vector};
+
+#[test]
+fn empty_bounding_box() {
+    let b = Bounds::empty();
+    assert!(b.is_empty());
+    assert!(!b.is_finite());
+    assert_eq!(b.surface_area(), 0.0);
+}
+
+#[test]
+fn adding_points_to_empty_box() {
+    let mut b = Bounds::empty();
+    b.add_point(Point::new(-5.0, 2.0, 0.0));
+    b.add_point(Point::new(7.0, 0.0, -3.0));
+    assert_eq!(b.min, Point::new(-5.0, 0.0, -3.0));
+    assert_eq!(b.max, Point::new(7.0, 2.0, 0.0));
+}
+
+#[test]
+fn merging_boxes() {
+    let b1 = Bounds::new(Point::new(-5.0, -2.0, 0.0), Point::new(7.0, 4.0, 4.0));
+    let b2 = Bounds::new(Point::new(8.0, -7.0, -2.0), Point::new(14.0, 2.0, 8.0));
+    let b = b1.merge(&b2);
+    assert_eq!(b.min, Point::new(-5.0, -7.0, -2.0));
+    assert_eq!(b.max, Point::new(14.0, 4.0, 8.0));
+    assert_eq!(b1.merge(&Bounds::empty()), b1);
+}
+
+#[test]
+fn box_contains_point() {
+    let b = Bounds::new(Point::new(5.0, -2.0, 0.0), Point::new(11.0, 4.0, 7.0));
+    assert!(b.contains_point(Point::new(5.0, -2.0, 0.0)));
+    assert!(b.contains_point(Point::new(8.0, 1.0, 3.0)));
+    assert!(!b.contains_point(Point::new(3.0, 0.0, 3.0)));
+    assert!(!b.contains_point(Point::new(8.0, -4.0, 3.0)));
+    assert!(!b.contains_point(Point::new(8.0, 1.0, 8.0)));
+}
+
+#[test]
+fn transforming_bounding_box() {
+    let b = Bounds::unit();
+    let t = rotate_x(PI / 4.0) * rotate_y(PI / 4.0);
+    let b2 = b.transform(&t);
+    assert_eq!(b2.min, Point::new(-SQRT_2, -1.70710, -1.70710));
+    assert_eq!(b2.max, Point::new(SQRT_2, 1.70710, 1.70710));
+}
+
+#[test]
+fn transforming_infinite_box_keeps_it_infinite() {
+    let p = Plane::new(Some(translate(0.0, -1.0, 0.0)), None);
+    let b = p.bounds();
+    assert_eq!(b.min.x, f64::NEG_INFINITY);
+    assert_eq!(b.max.z, f64::INFINITY);
+    assert_eq!(b.min.y, -1.0);
+    assert_eq!(b.max.y, -1.0);
+    let rotated = Plane::new(Some(rotate_x(PI / 4.0)), None).bounds();
+    assert!(!rotated.is_finite());
+    assert!(!rotated.min.y.is_nan());
+}
+
+#[test]
+fn bounds_of_shapes() {
+    let s = Sphere::new(Some(translate(1.0, -3.0, 5.0).scale(0.5, 2.0, 4.0)), None);
+    assert_eq!(s.bounds(), Bounds::new(Point::new(0.5, -5.0, 1.0), Point::new(1.5, -1.0, 9.0)));
+
+    let c = Cube::new(None, None);
+    assert_eq!(c.bounds(), Bounds::unit());
+
+    let mut cyl = Cylinder::new(None, None);
+    cyl.minimum = -5.0;
+    cyl.maximum = 3.0;
+    assert_eq!(cyl.bounds(), Bounds::new(Point::new(-1.0, -5.0, -1.0), Point::new(1.0, 3.0, 1.0)));
+    assert!(!Cylinder::new(None, None).bounds().is_finite());
+
+    let mut cone = Cone::new(None, None);
+    cone.minimum = -5.0;
+    cone.maximum = 3.0;
+    assert_eq!(cone.bounds(), Bounds::new(Point::new(-5.0, -5.0, -5.0), Point::new(5.0, 3.0, 5.0)));
+
+    let t = Triangle::new(Point::new(-3.0, 7.0, 2.0), Point::new(6.0, 2.0, -4.0), Point::new(2.0, -1.0, -1.0));
+    assert_eq!(t.bounds(), Bounds::new(Point::new(-3.0, -1.0, -4.0), Point::new(6.0, 7.0, 2.0)));
+}
+
+#[test]
+fn bounds_of_group_contains_its_children() {
+    let s = Sphere::new(Some(translate(2.0, 5.0, -3.0).scale(2.0, 2.0, 2.0)), None);
+    let mut c = Cylinder::new(Some(translate(-4.0, -1.0, 4.0).scale(0.5, 1.0, 0.5)), None);
+    c.minimum = -2.0;
+    c.maximum = 2.0;
+    let mut g = Group::new(Some(scale(2.0, 2.0, 2.0)));
+    g.add_children(vec![s.clone_box(), c.clone_box()]);
+    let b = g.bounds();
+    assert_eq!(b.min, Point::new(-9.0, -6.0, -10.0));
+    assert_eq!(b.max, Point::new(8.0, 14.0, 9.0));
+}
+
+#[test]
+fn intersecting_ray_with_bounding_box() {
+    let b = Bounds::new(Point::new(5.0, -2.0, 0.0), Point::new(11.0, 4.0, 7.0));
+    let cases = vec![
+        (Point::new(15.0, 1.0, 2.0), Vector::new(-1.0, 0.0, 0.0), true),
+        (Point::new(-5.0, -1.0, 4.0), Vector::new(1.0, 0.0, 0.0), true),
+        (Point::new(7.0, 6.0, 5.0), Vector::new(0.0, -1.0, 0.0), true),
+        (Point::new(9.0, -5.0, 6.0), Vector::new(0.0, 1.0, 0.0), true),
+        (Point::new(8.0, 2.0, 12.0), Vector::new(0.0, 0.0, -1.0), true),
+        (Point::new(6.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0), true),
+        (Point::new(8.0, 1.0, 3.5), Vector::new(0.0, 0.0, 1.0), true),
+        (Point::new(9.0, -1.0, -8.0), Vector::new(2.0, 4.0, 6.0), false),
+        (Point::new(8.0, 3.0, -4.0), Vector::new(6.0, 2.0, 4.0), false),
+        (Point::new(9.0, -1.0, -2.0), Vector::new(4.0, 6.0, 2.0), false),
+        (Point::new(4.0, 0.0, 9.0), Vector::new(0.0, 0.0, -1.0), false),
+        (Point::new(8.0, 6.0, -1.0), Vector::new(0.0, -1.0, 0.0), false),
+        (Point::new(12.0, 5.0, 4.0), Vector::new(-1.0, 0.0, 0.0), false),
+    ];
+    for (origin, direction, result) in cases {
+        let r = Ray::new(origin, direction.normalize());
+        assert_eq!(b.intersects(r), result);
+    }
+}
+
+#[test]
+fn ray_pointing_away_from_box_still_crosses_its_line() {
+    let b = Bounds::unit();
+    let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, -1.0));
+    assert_eq!(b.intersect(r), Some((-6.0, -4.0)));
+}
@@ -0,0 +1,94 @@
+use raytracer::bvh::Bvh;
+use raytracer::matrix::mat4::translate;
+use raytracer::ray::Ray;
+use raytracer::shape::{Group, Plane, Shape, Sphere};
+use raytracer::tuple::{Point, Vector};
+use raytracer::world::World;
+
+fn grid_of_spheres(n: usize) -> Vec<Box<dyn Shape>> {
+    let mut shapes: Vec<Box<dyn Shape>> = vec![];
+    for i in 0..n {
+        for j in 0..n {
+            let t = translate(i as f64 * 3.0, j as f64 * 3.0, 0.0);
+            shapes.push(Box::new(Sphere::new(Some(t), None)));
+        }
+    }
+    shapes
+}
+
+fn sorted_ts(mut xs: Vec<f64>) -> Vec<f64> {
+    xs.sort_by(|a, b| a.partial_cmp(b).unwrap());
+    xs
+}
+
+#[test]
+fn building_bvh_splits_the_shapes() {
+    let shapes = grid_of_spheres(10);
+    let bvh = Bvh::build(&shapes);
+    assert_eq!(bvh.len(), 100);
+    assert!(bvh.node_count() > 1);
+    assert_eq!(bvh.bounds().min, Point::new(-1.0, -1.0, -1.0));
+    assert_eq!(bvh.bounds().max, Point::new(28.0, 28.0, 1.0));
+}
+
+#[test]
+fn bvh_finds_the_same_intersections_as_a_linear_search() {
+    let shapes = grid_of_spheres(10);
+    let bvh = Bvh::build(&shapes);
+    let rays = vec![
+        Ray::new(Point::new(3.0, 6.0, -5.0), Vector::new(0.0, 0.0, 1.0)),
+        Ray::new(Point::new(-5.0, 0.0, 0.0), Vector::new(1.0, 0.0, 0.0)),
+        Ray::new(Point::new(-5.0, -5.0, 0.0), Vector::new(1.0, 1.0, 0.0).normalize()),
+        Ray::new(Point::new(1.5, 1.5, -5.0), Vector::new(0.0, 0.0, 1.0)),
+        Ray::new(Point::new(40.0, 3.0, 0.0), Vector::new(1.0, 0.0, 0.0)),
+    ];
+    for r in rays {
+        let linear = shapes.iter().flat_map(|s| s.intersect(r).xs).map(|i| i.t).collect();
+        let fast = bvh.intersect(&shapes, r).into_iter().map(|i| i.t).collect();
+        assert_eq!(sorted_ts(fast), sorted_ts(linear));
+    }
+}
+
+#[test]
+fn unbounded_shapes_are_always_tested() {
+    let mut shapes = grid_of_spheres(3);
+    shapes.push(Box::new(Plane::new(None, None)));
+    let bvh = Bvh::build(&shapes);
+    let r = Ray::new(Point::new(100.0, 10.0, 100.0), Vector::new(0.0, -1.0, 0.0));
+    let xs = bvh.intersect(&shapes, r);
+    assert_eq!(xs.len(), 1);
+    assert_eq!(xs[0].t, 10.0);
+}
+
+#[test]
+fn world_and_groups_use_the_hierarchy() {
+    let mut g = Group::new(Some(translate(0.0, 0.0, 10.0)));
+    g.add_children(grid_of_spheres(8));
+    let mut w = World::new(None, Some(grid_of_spheres(8)));
+    let r = Ray::new(Point::new(6.0, 9.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+    assert_eq!(w.intersect(r).len(), 2);
+    w.objects.push(Box::new(g));
+    // objects pushed after the hierarchy was built are still found
+    assert_eq!(w.intersect(r).len(), 4);
+    w.build_bvh();
+    let xs = w.intersect(r);
+    assert_eq!(xs.len(), 4);
+    assert_eq!(xs[0].t, 4.0);
+    assert_eq!(xs[3].t, 16.0);
+}
+
+#[test]
+fn replacing_an_object_rebuilds_the_hierarchy() {
+    let mut w = World::new(None, Some(grid_of_spheres(4)));
+    let r = Ray::new(Point::new(100.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+    assert_eq!(w.intersect(r).len(), 0);
+    // the same sphere, moved in front of the ray
+    let mut moved = w.objects[0].clone_box();
+    moved.set_transform(translate(100.0, 0.0, 0.0));
+    w.set_object(0, moved);
+    assert_eq!(w.intersect(r).len(), 2);
+    // and the same goes for the objects added to the world
+    w.add_object(Box::new(Sphere::new(Some(translate(100.0, 0.0, 3.0)), None)));
+    assert_eq!(w.intersect(r).len(), 4);
+    assert_eq!(w.bvh().len(), 17);
+}
@@ -1,7 +1,11 @@
 use raytracer::camera::Camera;
-use raytracer::color::Color;
+use raytracer::color::{Color, WHITE};
+use raytracer::light::AreaLight;
+use raytracer::material::Material;
 use raytracer::matrix::mat4::*;
 use raytracer::{f64eq, tuple::*};
+use raytracer::shape::{Plane, Sphere};
+use raytracer::sampler::{Adaptive, Filter, Sampler};
 use raytracer::world::World;
 use std::f64::consts::PI;
 
@@ -68,3 +72,136 @@ fn rendering_a_world_with_camera() {
     let image = c.render(w);
     assert_eq!(image[(5, 5)], Color::new(0.38066, 0.47583, 0.2855))
 }
+
+#[test]
+fn camera_renders_on_all_cores_by_default() {
+    let c = Camera::new(160, 120, PI / 2.0);
+    assert!(c.threads >= 1);
+}
+
+#[test]
+fn threaded_render_is_identical_to_single_threaded() {
+    // soft shadows, reflection and refraction all in the same scene
+    let mut w = World::default();
+    w.lights = vec![Box::new(AreaLight::with_samples(
+        Point::new(-10.5, 10., -10.5), Vector::new(1., 0., 0.), Vector::new(0., 0., 1.), 4, WHITE))];
+    let floor = Material { reflective: 0.5, ..Material::default() };
+    w.objects.push(Box::new(Plane::new(Some(translate(0., -1., 0.)), Some(floor))));
+    w.objects.push(Box::new(Sphere::new(Some(translate(1.5, 0., -1.)), Some(Material::glass()))));
+
+    let mut c = Camera::new(40, 30, PI / 2.0);
+    c.set_transform(view_transform(Point::new(0., 1., -5.), Point::new(0., 0., 0.), Vector::new(0., 1., 0.)));
+    c.threads = 1;
+    let single = c.render(w.clone());
+    for threads in [2, 3, 8, 100] {
+        c.threads = threads;
+        let image = c.render(w.clone());
+        assert_eq!(image, single);
+        assert_eq!(image.to_raw_buffer(), single.to_raw_buffer());
+    }
+}
+
+#[test]
+fn ray_through_an_offset_in_the_pixel() {
+    let c = Camera::new(201, 101, PI / 2.0);
+    let center = c.ray_for_pixel_offset(100, 50, 0.5, 0.5);
+    assert_eq!(center.direction, c.ray_for_pixel(100, 50).direction);
+    // the far corner of a pixel is the near corner of the next one
+    let far = c.ray_for_pixel_offset(10, 20, 1.0, 1.0);
+    let near = c.ray_for_pixel_offset(11, 21, 0.0, 0.0);
+    assert_eq!(far.direction, near.direction);
+    let r = c.ray_for_pixel_offset(0, 0, 0.0, 0.0);
+    assert_eq!(r.direction, Vector::new(1.0, 101.0 / 201.0, -1.0).normalize());
+}
+
+#[test]
+fn supersampling_blends_the_edges() {
+    let w = World::default();
+    let mut c = Camera::new(21, 21, PI / 4.0);
+    c.set_transform(view_transform(Point::new(0., 0., -5.), Point::new(0., 0., 0.), Vector::new(0., 1., 0.)));
+    let single = c.render(w.clone());
+    // the first pixel of the middle row whose center is on the sphere
+    let edge = (0..21).find(|&x| single[(x, 10)] != single[(0, 10)]).unwrap();
+    c.samples = 16;
+    for sampler in [Sampler::Grid, Sampler::Jittered, Sampler::Halton] {
+        for filter in [Filter::Box, Filter::Tent, Filter::Gaussian(0.5)] {
+            c.sampler = sampler;
+            c.filter = filter;
+            let image = c.render(w.clone());
+            // the flat background doesn't change
+            assert_eq!(image[(0, 10)], single[(0, 10)]);
+            // the silhouette of the sphere gets softer
+            let blended = image[(edge, 10)];
+            assert!(blended.g > single[(0, 10)].g && blended.g < single[(edge, 10)].g);
+        }
+    }
+}
+
+#[test]
+fn threaded_supersampling_is_identical_to_single_threaded() {
+    let w = World::default();
+    let mut c = Camera::new(20, 20, PI / 2.0);
+    c.set_transform(view_transform(Point::new(0., 0., -5.), Point::new(0., 0., 0.), Vector::new(0., 1., 0.)));
+    c.samples = 5;
+    c.sampler = Sampler::Jittered;
+    c.filter = Filter::Gaussian(0.5);
+    c.threads = 1;
+    let single = c.render(w.clone());
+    c.threads = 4;
+    assert_eq!(c.render(w).to_raw_buffer(), single.to_raw_buffer());
+}
+
+#[test]
+fn adaptive_sampling_only_refines_the_edges() {
+    let w = World::default();
+    let mut c = Camera::new(21, 21, PI / 4.0);
+    c.set_transform(view_transform(Point::new(0., 0., -5.), Point::new(0., 0., 0.), Vector::new(0., 1., 0.)));
+    let single = c.render(w.clone());
+    c.samples = 4;
+    let mut refined = 0;
+    c.adaptive = Some(Adaptive::new(0.05, 1));
+    let image = c.render(w.clone());
+    c.adaptive = None;
+    for y in 0..21 {
+        for x in 0..21 {
+            // every pixel either kept its single sample or got 4
+            if image[(x, y)] != single[(x, y)] {
+                assert_eq!(image[(x, y)], c.pixel_color(&w, x, y));
+                refined += 1;
+            }
+        }
+    }
+    assert!(refined > 0);
+    assert!(refined < 21 * 21 / 4);
+    // the background far from the sphere is never refined
+    assert_eq!(image[(0, 0)], single[(0, 0)]);
+}
+
+#[test]
+fn adaptive_sampling_with_nothing_to_refine() {
+    let w = World::default();
+    let mut c = Camera::new(21, 21, PI / 4.0);
+    c.set_transform(view_transform(Point::new(0., 0., -5.), Point::new(0., 0., 0.), Vector::new(0., 1., 0.)));
+    let single = c.render(w.clone());
+    // samples is ignored, the first pass always has one sample
+    c.samples = 16;
+    c.adaptive = Some(Adaptive::new(10.0, 3));
+    assert_eq!(c.render(w.clone()), single);
+    c.adaptive = Some(Adaptive::new(0.05, 0));
+    assert_eq!(c.render(w), single);
+}
+
+#[test]
+fn adaptive_sampling_goes_deeper_on_sharp_edges() {
+    let w = World::default();
+    let mut c = Camera::new(21, 21, PI / 4.0);
+    c.set_transform(view_transform(Point::new(0., 0., -5.), Point::new(0., 0., 0.), Vector::new(0., 1., 0.)));
+    c.adaptive = Some(Adaptive::new(0.05, 1));
+    let shallow = c.render(w.clone());
+    c.adaptive = Some(Adaptive::new(0.05, 3));
+    c.threads = 1;
+    let deep = c.render(w.clone());
+    assert_ne!(deep, shallow);
+    c.threads = 4;
+    assert_eq!(c.render(w).to_raw_buffer(), deep.to_raw_buffer());
+}
@@ -1,3 +1,4 @@
+use std::convert::TryInto;
 use raytracer::{canvas::Canvas, color::{BLACK, BLUE, Color, RED, WHITE}};
 
 #[test]
@@ -35,4 +36,247 @@ fn save_canvas() {
     c.backgound(BLUE);
     c.save("./render/test.png").unwrap();
     assert_eq!(c[(0, 0)], BLUE);
-}
\ No newline at end of file
+}
+
+#[test]
+fn ppm_header() {
+    let c = Canvas::new(5, 3);
+    let ppm = c.to_ppm();
+    let lines: Vec<&str> = ppm.lines().collect();
+    assert_eq!(lines[0..3], ["P3", "5 3", "255"]);
+}
+
+#[test]
+fn ppm_pixel_data() {
+    let mut c = Canvas::new(5, 3);
+    c[(0, 0)] = Color::new(1.5, 0., 0.);
+    c[(2, 1)] = Color::new(0., 0.5, 0.);
+    c[(4, 2)] = Color::new(-0.5, 0., 1.);
+    let ppm = c.to_ppm();
+    let lines: Vec<&str> = ppm.lines().collect();
+    assert_eq!(lines[3..6], [
+        "255 0 0 0 0 0 0 0 0 0 0 0 0 0 0",
+        "0 0 0 0 0 0 0 128 0 0 0 0 0 0 0",
+        "0 0 0 0 0 0 0 0 0 0 0 0 0 0 255",
+    ]);
+}
+
+#[test]
+fn ppm_splits_long_lines() {
+    let mut c = Canvas::new(10, 2);
+    c.backgound(Color::new(1., 0.8, 0.6));
+    let ppm = c.to_ppm();
+    let lines: Vec<&str> = ppm.lines().collect();
+    assert_eq!(lines[3..7], [
+        "255 204 153 255 204 153 255 204 153 255 204 153 255 204 153 255 204",
+        "153 255 204 153 255 204 153 255 204 153 255 204 153",
+        "255 204 153 255 204 153 255 204 153 255 204 153 255 204 153 255 204",
+        "153 255 204 153 255 204 153 255 204 153 255 204 153",
+    ]);
+    assert!(ppm.lines().all(|l| l.len() <= 70));
+}
+
+#[test]
+fn ppm_ends_with_newline() {
+    let c = Canvas::new(5, 3);
+    assert!(c.to_ppm().ends_with('\n'));
+}
+
+#[test]
+fn binary_ppm() {
+    let mut c = Canvas::new(2, 1);
+    c[(0, 0)] = RED;
+    c[(1, 0)] = Color::new(0., 0.5, 1.);
+    let mut expected = b"P6\n2 1\n255\n".to_vec();
+    expected.extend([255, 0, 0, 0, 128, 255]);
+    assert_eq!(c.to_ppm_binary(), expected);
+}
+
+#[test]
+fn reading_ppm_with_wrong_magic_number() {
+    let ppm = "P32\n1 1\n255\n0 0 0\n";
+    assert!(Canvas::from_ppm(ppm.as_bytes()).is_err());
+}
+
+#[test]
+fn reading_ppm_size_and_pixels() {
+    let ppm = "P3
+4 3
+255
+255 127 0  0 127 255  127 255 0  255 255 255
+0 0 0  255 0 0  0 255 0  0 0 255
+255 255 0  0 255 255  255 0 255  127 127 127
+";
+    let c = Canvas::from_ppm(ppm.as_bytes()).unwrap();
+    assert_eq!((c.width, c.height), (4, 3));
+    assert_eq!(c[(0, 0)], Color::new(1., 127. / 255., 0.));
+    assert_eq!(c[(1, 0)], Color::new(0., 127. / 255., 1.));
+    assert_eq!(c[(3, 0)], Color::new(1., 1., 1.));
+    assert_eq!(c[(1, 1)], Color::new(1., 0., 0.));
+    assert_eq!(c[(3, 2)], Color::new(127. / 255., 127. / 255., 127. / 255.));
+}
+
+#[test]
+fn reading_ppm_skips_comments_and_scales_values() {
+    let ppm = "P3
+# this is a comment
+2 1
+# this, too
+100
+100 100 100  # and here
+0 50
+100
+";
+    let c = Canvas::from_ppm(ppm.as_bytes()).unwrap();
+    assert_eq!(c[(0, 0)], Color::new(1., 1., 1.));
+    // the last pixel's values span two lines
+    assert_eq!(c[(1, 0)], Color::new(0., 0.5, 1.));
+}
+
+#[test]
+fn reading_incomplete_ppm() {
+    assert!(Canvas::from_ppm(b"P3\n2 1\n255\n1 2 3 4\n").is_err());
+    assert!(Canvas::from_ppm(b"P6\n2 1\n255\n\x01\x02\x03").is_err());
+    assert!(Canvas::from_ppm(b"P3\n2 x\n255\n").is_err());
+}
+
+#[test]
+fn reading_ppm_too_large_for_its_data() {
+    // the size overflows a usize
+    let huge = format!("P6\n{} {}\n65535\n\x01\x02", usize::MAX / 2, 3);
+    assert!(Canvas::from_ppm(huge.as_bytes()).is_err());
+    // the size fits, but not in a few bytes
+    let e = Canvas::from_ppm(b"P3\n100000 100000\n255\n0 0 0\n").unwrap_err();
+    assert_eq!(e, "image size 100000x100000 is too large for 27 bytes of data");
+}
+
+#[test]
+fn reading_binary_ppm() {
+    let mut ppm = b"P6\n# comment\n2 1\n65535\n".to_vec();
+    ppm.extend([255, 255, 0, 0, 128, 0, 0, 0, 0, 0, 255, 255]);
+    let c = Canvas::from_ppm(&ppm).unwrap();
+    assert_eq!(c[(0, 0)], Color::new(1., 0., 0.5 + 0.5 / 65535.));
+    assert_eq!(c[(1, 0)], Color::new(0., 0., 1.));
+}
+
+#[test]
+fn ppm_round_trip() {
+    let mut c = Canvas::new(7, 5);
+    for y in 0..5 {
+        for x in 0..7 {
+            c[(x, y)] = Color::from(((x * 30) as u8, (y * 50) as u8, 200));
+        }
+    }
+    assert_eq!(Canvas::from_ppm(c.to_ppm().as_bytes()).unwrap(), c);
+    assert_eq!(Canvas::from_ppm(&c.to_ppm_binary()).unwrap(), c);
+
+    let file = std::env::temp_dir().join(format!("raytracer-canvas-{}.ppm", std::process::id()));
+    let file = file.to_str().unwrap();
+    c.save(file).unwrap();
+    assert!(std::fs::read_to_string(file).unwrap().starts_with("P3\n7 5\n255\n"));
+    assert_eq!(Canvas::load(file).unwrap(), c);
+    std::fs::remove_file(file).unwrap();
+}
+
+#[test]
+fn hdr_header_and_rgbe_pixels() {
+    let mut c = Canvas::new(2, 1);
+    c[(0, 0)] = Color::new(1., 1., 1.);
+    c[(1, 0)] = Color::new(0.5, 0.25, -1.);
+    let hdr = c.to_hdr();
+    let header = b"#?RADIANCE\nFORMAT=32-bit_rle_rgbe\n\n-Y 1 +X 2\n";
+    assert_eq!(hdr[..header.len()], header[..]);
+    // rows narrower than 8 pixels are not run-length encoded
+    assert_eq!(hdr[header.len()..], [128, 128, 128, 129, 128, 64, 0, 128]);
+}
+
+#[test]
+fn hdr_keeps_colors_above_one() {
+    let mut c = Canvas::new(20, 3);
+    for y in 0..3 {
+        for x in 0..20 {
+            c[(x, y)] = Color::new(x as f64 * 0.75, y as f64 * 10., 0.001 * x as f64);
+        }
+    }
+    let hdr = c.to_hdr();
+    let decoder = image::codecs::hdr::HdrDecoder::new(&hdr[..]).unwrap();
+    let pixels = decoder.read_image_hdr().unwrap();
+    assert_eq!(pixels.len(), 60);
+    for (i, p) in pixels.iter().enumerate() {
+        let expected = c[(i % 20, i / 20)];
+        let max = expected.r.max(expected.g).max(expected.b);
+        for (v, e) in [(p[0], expected.r), (p[1], expected.g), (p[2], expected.b)] {
+            assert!((v as f64 - e).abs() <= max / 128., "{:?} != {:?}", p, expected);
+        }
+    }
+}
+
+#[test]
+fn pfm_stores_floats_bottom_up() {
+    let mut c = Canvas::new(1, 2);
+    c[(0, 0)] = Color::new(2.5, 0., 0.);
+    c[(0, 1)] = Color::new(0., 0., 100.);
+    let pfm = c.to_pfm();
+    let header = b"PF\n1 2\n-1.0\n";
+    assert_eq!(pfm[..header.len()], header[..]);
+    let floats: Vec<f32> = pfm[header.len()..]
+        .chunks(4)
+        .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+        .collect();
+    assert_eq!(floats, [0., 0., 100., 2.5, 0., 0.]);
+}
+
+#[test]
+fn exr_layout() {
+    let mut c = Canvas::new(2, 3);
+    c[(0, 0)] = Color::new(1., 2., 3.);
+    c[(1, 2)] = Color::new(40., 50., 60.);
+    let exr = c.to_exr();
+    assert_eq!(exr[..8], [0x76, 0x2f, 0x31, 0x01, 2, 0, 0, 0]);
+    let find = |needle: &[u8]| exr.windows(needle.len()).position(|w| w == needle);
+    assert!(find(b"channels\0chlist\0").is_some());
+    assert!(find(b"compression\0compression\0\x01\0\0\0\0").is_some());
+    assert!(find(b"dataWindow\0box2i\0").is_some());
+
+    // the offset table follows the header, one entry per row
+    let header_end = find(b"screenWindowWidth\0float\0").unwrap() + 24 + 4 + 4 + 1;
+    let offset = |y: usize| {
+        let at = header_end + y * 8;
+        u64::from_le_bytes(exr[at..at + 8].try_into().unwrap()) as usize
+    };
+    let float = |at: usize| f32::from_le_bytes(exr[at..at + 4].try_into().unwrap());
+    let int = |at: usize| i32::from_le_bytes(exr[at..at + 4].try_into().unwrap());
+    assert_eq!(offset(0), header_end + 3 * 8);
+    for y in 0..3 {
+        assert_eq!(int(offset(y)), y as i32);
+        assert_eq!(int(offset(y) + 4), 2 * 3 * 4);
+    }
+    // B, G and R of each row in turn
+    let row0 = offset(0) + 8;
+    assert_eq!([float(row0), float(row0 + 8), float(row0 + 16)], [3., 2., 1.]);
+    let row2 = offset(2) + 8;
+    assert_eq!([float(row2 + 4), float(row2 + 12), float(row2 + 20)], [60., 50., 40.]);
+    assert_eq!(exr.len(), offset(2) + 8 + 24);
+}
+
+#[test]
+fn save_chooses_the_format_by_extension() {
+    let mut c = Canvas::new(3, 2);
+    c.backgound(Color::new(4., 2., 1.));
+    for (ext, expected) in [("hdr", c.to_hdr()), ("pfm", c.to_pfm()), ("exr", c.to_exr()), ("PFM", c.to_pfm())] {
+        let file = std::env::temp_dir().join(format!("raytracer-canvas-{}.{}", std::process::id(), ext));
+        c.save(file.to_str().unwrap()).unwrap();
+        assert_eq!(std::fs::read(&file).unwrap(), expected);
+        std::fs::remove_file(file).unwrap();
+    }
+}
+
+#[test]
+fn writing_an_empty_canvas() {
+    let c = Canvas::new(0, 2);
+    assert_eq!(c.to_ppm(), "P3\n0 2\n255\n\n\n");
+    assert_eq!(c.to_ppm_binary(), b"P6\n0 2\n255\n");
+    assert!(c.to_hdr().ends_with(b"-Y 2 +X 0\n"));
+    assert_eq!(c.to_pfm(), b"PF\n0 2\n-1.0\n");
+    assert!(!Canvas::new(0, 0).to_exr().is_empty());
+}
@@ -0,0 +1,134 @@
+use raytracer::canvas::Canvas;
+use std::fs;
+use std::path::PathBuf;
+use std::process::{Command, Output};
+
+fn raytracer(args: &[&str]) -> Output {
+    Command::new(env!("CARGO_BIN_EXE_raytracer"))
+        .args(args)
+        .output()
+        .unwrap()
+}
+
+/// A directory of its own for every test, so they can run in parallel
+fn temp_dir(name: &str) -> PathBuf {
+    let dir = std::env::temp_dir().join(format!("raytracer-cli-{}-{}", name, std::process::id()));
+    fs::create_dir_all(&dir).unwrap();
+    dir
+}
+
+const SCENE: &str = "
+- add: camera
+  width: 40
+  height: 20
+  field-of-view: 1.0
+  from: [ 0, 1.5, -5 ]
+  to: [ 0, 1, 0 ]
+  up: [ 0, 1, 0 ]
+- add: light
+  at: [ -10, 10, -10 ]
+  intensity: [ 1, 1, 1 ]
+- add: sphere
+  transform:
+    - [ translate, 0, 1, 0 ]
+";
+
+#[test]
+fn rendering_a_scene_file() {
+    let dir = temp_dir("scene");
+    let scene = dir.join("ball.yml");
+    fs::write(&scene, SCENE).unwrap();
+    let output = dir.join("ball.png");
+    let out = raytracer(&[
+        scene.to_str().unwrap(), "-o", output.to_str().unwrap(), "-r", "30x10", "-s", "4", "-t", "2",
+    ]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("Rendered ball at 30x10, 4 samples per pixel on 2 threads in"), "{}", stdout);
+    let image = Canvas::load(output.to_str().unwrap()).unwrap();
+    assert_eq!((image.width, image.height), (30, 10));
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn drawing_a_demo() {
+    let dir = temp_dir("demo");
+    let output = dir.join("clock.png");
+    let out = raytracer(&["--demo", "clock", "--output", output.to_str().unwrap()]);
+    assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+    assert!(output.exists());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn tone_mapping_before_saving() {
+    let dir = temp_dir("tone");
+    let scene = dir.join("ball.yml");
+    fs::write(&scene, SCENE).unwrap();
+    let render = |name: &str, extra: &[&str]| {
+        let output = dir.join(name);
+        let mut args = vec![scene.to_str().unwrap(), "-o", output.to_str().unwrap()];
+        args.extend(extra);
+        let out = raytracer(&args);
+        assert!(out.status.success(), "{}", String::from_utf8_lossy(&out.stderr));
+        output
+    };
+    // the red channel of every pixel, top row first
+    let pfm = |name: &str, extra: &[&str]| {
+        let data = fs::read(render(name, extra)).unwrap();
+        let start = data.len() - 40 * 20 * 12;
+        let reds: Vec<f32> = data[start..]
+            .chunks(12)
+            .map(|b| f32::from_le_bytes([b[0], b[1], b[2], b[3]]))
+            .collect();
+        reds.chunks(40).rev().flatten().copied().collect::<Vec<_>>()
+    };
+    let plain = pfm("plain.pfm", &[]);
+    // the HDR formats only get the exposure, the colors above 1 are kept
+    let exposed = pfm("exposed.pfm", &["--exposure", "2"]);
+    assert!(exposed.iter().any(|&c| c > 1.));
+    for (p, e) in plain.iter().zip(exposed.iter()) {
+        assert!((e - 4. * p).abs() < 1e-5, "{} != {}", e, 4. * p);
+    }
+    let mapped = Canvas::load(render("mapped.ppm", &["--exposure", "2", "--tone", "reinhard"]).to_str().unwrap()).unwrap();
+    for (i, p) in plain.iter().enumerate() {
+        let c = 4. * p.max(0.) as f64;
+        let expected = c / (1. + c);
+        let r = mapped[(i % 40, i / 40)].r;
+        assert!((r - expected).abs() < 0.01, "{} != {}", r, expected);
+    }
+    // a tone operator would clip the HDR formats
+    let exr = dir.join("mapped.exr");
+    let out = raytracer(&[scene.to_str().unwrap(), "-o", exr.to_str().unwrap(), "--tone", "aces"]);
+    assert!(!out.status.success());
+    assert!(String::from_utf8_lossy(&out.stderr).contains("keep the colors above 1"));
+    assert!(!exr.exists());
+    fs::remove_dir_all(dir).unwrap();
+}
+
+#[test]
+fn errors_exit_with_a_failure() {
+    for args in [
+        vec![],
+        vec!["--demo", "teapot"],
+        vec!["--frobnicate"],
+        vec!["-r", "wide", "scene.yml"],
+        vec!["-s", "0", "scene.yml"],
+        vec!["no/such/scene.yml"],
+        vec!["--demo", "clock", "--samples", "4"],
+        vec!["--exposure", "bright", "scene.yml"],
+        vec!["--tone", "filmic", "scene.yml"],
+    ] {
+        let out = raytracer(&args);
+        assert!(!out.status.success(), "{:?} should fail", args);
+        assert!(String::from_utf8_lossy(&out.stderr).starts_with("error: "));
+    }
+}
+
+#[test]
+fn listing_the_demos() {
+    let out = raytracer(&["--list"]);
+    assert!(out.status.success());
+    let stdout = String::from_utf8_lossy(&out.stdout);
+    assert!(stdout.contains("ch7") && stdout.contains("ch9"));
+}
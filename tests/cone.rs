@@ -0,0 +1,68 @@
+use raytracer::ray::Ray;
+use raytracer::shape::Cone;
+use raytracer::shape::Shape;
+use raytracer::tuple::{Point, Vector};
+
+#[test]
+fn intersecting_cone_with_ray() {
+    let shape = Cone::new(None, None);
+    let cases = vec![
+        (Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0), 5.0, 5.0),
+        (Point::new(0.0, 0.0, -5.0), Vector::new(1.0, 1.0, 1.0), 8.66025, 8.66025),
+        (Point::new(1.0, 1.0, -5.0), Vector::new(-0.5, -1.0, 1.0), 4.55006, 49.44994),
+    ];
+    for (origin, direction, t0, t1) in cases {
+        let xs = shape.intersect(Ray::new(origin, direction.normalize()));
+        assert_eq!(xs.len(), 2);
+        assert!((xs[0].t - t0).abs() < 0.0001);
+        assert!((xs[1].t - t1).abs() < 0.0001);
+    }
+}
+
+#[test]
+fn intersecting_cone_with_ray_parallel_to_one_half() {
+    let shape = Cone::new(None, None);
+    let direction = Vector::new(0.0, 1.0, 1.0).normalize();
+    let xs = shape.intersect(Ray::new(Point::new(0.0, 0.0, -1.0), direction));
+    assert_eq!(xs.len(), 1);
+    assert!((xs[0].t - 0.35355).abs() < 0.0001);
+}
+
+#[test]
+fn intersecting_cone_end_caps() {
+    let mut shape = Cone::new(None, None);
+    shape.minimum = -0.5;
+    shape.maximum = 0.5;
+    shape.closed = true;
+    let cases = vec![
+        (Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 1.0, 0.0), 0),
+        (Point::new(0.0, 0.0, -0.25), Vector::new(0.0, 1.0, 1.0), 2),
+        (Point::new(0.0, 0.0, -0.25), Vector::new(0.0, 1.0, 0.0), 4),
+    ];
+    for (origin, direction, count) in cases {
+        let xs = shape.intersect(Ray::new(origin, direction.normalize()));
+        assert_eq!(xs.len(), count);
+    }
+}
+
+#[test]
+fn normal_on_cone() {
+    let shape = Cone::new(None, None);
+    let cases = vec![
+        (Point::new(1.0, 1.0, 1.0), Vector::new(1.0, -2f64.sqrt(), 1.0)),
+        (Point::new(-1.0, -1.0, 0.0), Vector::new(-1.0, 1.0, 0.0)),
+    ];
+    for (point, normal) in cases {
+        assert_eq!(shape.normal_at(point), normal.normalize());
+    }
+}
+
+#[test]
+fn normal_on_cone_end_caps() {
+    let mut shape = Cone::new(None, None);
+    shape.minimum = -1.0;
+    shape.maximum = 1.0;
+    shape.closed = true;
+    assert_eq!(shape.normal_at(Point::new(0.5, 1.0, 0.0)), Vector::new(0.0, 1.0, 0.0));
+    assert_eq!(shape.normal_at(Point::new(0.0, -1.0, 0.5)), Vector::new(0.0, -1.0, 0.0));
+}
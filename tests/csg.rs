@@ -0,0 +1,133 @@
+use raytracer::intersection::{Intersection, Intersections};
+use raytracer::matrix::mat4::{scale, translate};
+use raytracer::ray::Ray;
+use raytracer::shape::{Csg, CsgOperation, Cube, Group, Shape, Sphere};
+use raytracer::tuple::{Point, Vector};
+
+#[test]
+fn csg_is_created_with_operation_and_two_shapes() {
+    let s1 = Sphere::new(None, None);
+    let s2 = Cube::new(None, None);
+    let c = Csg::new(CsgOperation::Union, s1.clone_box(), s2.clone_box());
+    assert_eq!(c.operation, CsgOperation::Union);
+    assert_eq!(c.left().id(), s1.id);
+    assert_eq!(c.right().id(), s2.id);
+}
+
+#[test]
+fn evaluating_rule_for_csg_operation() {
+    use CsgOperation::*;
+    let cases = vec![
+        (Union, true, true, true, false),
+        (Union, true, true, false, true),
+        (Union, true, false, true, false),
+        (Union, true, false, false, true),
+        (Union, false, true, true, false),
+        (Union, false, true, false, false),
+        (Union, false, false, true, true),
+        (Union, false, false, false, true),
+        (Intersection, true, true, true, true),
+        (Intersection, true, true, false, false),
+        (Intersection, true, false, true, true),
+        (Intersection, true, false, false, false),
+        (Intersection, false, true, true, true),
+        (Intersection, false, true, false, true),
+        (Intersection, false, false, true, false),
+        (Intersection, false, false, false, false),
+        (Difference, true, true, true, false),
+        (Difference, true, true, false, true),
+        (Difference, true, false, true, false),
+        (Difference, true, false, false, true),
+        (Difference, false, true, true, true),
+        (Difference, false, true, false, true),
+        (Difference, false, false, true, false),
+        (Difference, false, false, false, false),
+    ];
+    for (op, lhit, inl, inr, result) in cases {
+        assert_eq!(op.intersection_allowed(lhit, inl, inr), result);
+    }
+}
+
+#[test]
+fn filtering_list_of_intersections() {
+    let cases = vec![
+        (CsgOperation::Union, 0, 3),
+        (CsgOperation::Intersection, 1, 2),
+        (CsgOperation::Difference, 0, 1),
+    ];
+    for (op, x0, x1) in cases {
+        let s1 = Sphere::new(None, None);
+        let s2 = Cube::new(None, None);
+        let c = Csg::new(op, s1.clone_box(), s2.clone_box());
+        let xs = Intersections::new(vec![
+            Intersection::new(1.0, s1.clone_box()),
+            Intersection::new(2.0, s2.clone_box()),
+            Intersection::new(3.0, s1.clone_box()),
+            Intersection::new(4.0, s2.clone_box()),
+        ]);
+        let result = c.filter_intersections(xs.clone());
+        assert_eq!(result.len(), 2);
+        assert_eq!(result[0], xs[x0]);
+        assert_eq!(result[1], xs[x1]);
+    }
+}
+
+#[test]
+fn ray_misses_csg_object() {
+    let c = Csg::new(
+        CsgOperation::Union,
+        Box::new(Sphere::new(None, None)),
+        Box::new(Cube::new(None, None)),
+    );
+    let r = Ray::new(Point::new(0.0, 2.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+    assert_eq!(c.intersect(r).len(), 0);
+}
+
+#[test]
+fn ray_hits_csg_object() {
+    let s1 = Sphere::new(None, None);
+    let s2 = Sphere::new(Some(translate(0.0, 0.0, 0.5)), None);
+    let c = Csg::new(CsgOperation::Union, s1.clone_box(), s2.clone_box());
+    let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+    let xs = c.intersect(r);
+    assert_eq!(xs.len(), 2);
+    assert_eq!(xs[0].t, 4.0);
+    assert_eq!(xs[0].object.id(), s1.id);
+    assert_eq!(xs[1].t, 6.5);
+    assert_eq!(xs[1].object.id(), s2.id);
+}
+
+#[test]
+fn drilling_hole_in_cube() {
+    // a cube minus a thin box along the z axis: a ray down the hole passes
+    let hole = Cube::new(Some(scale(0.2, 0.2, 2.0)), None);
+    let c = Csg::new(CsgOperation::Difference, Box::new(Cube::new(None, None)), Box::new(hole));
+    let through = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+    assert_eq!(c.intersect(through).len(), 0);
+    let beside = Ray::new(Point::new(0.5, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+    assert_eq!(c.intersect(beside).len(), 2);
+}
+
+#[test]
+fn csg_can_nest_groups_and_other_csg() {
+    let s1 = Sphere::new(None, None);
+    let mut g = Group::new(None);
+    g.add_child(s1.clone_box());
+    let inner = Csg::new(
+        CsgOperation::Intersection,
+        Box::new(g),
+        Box::new(Sphere::new(Some(translate(0.0, 0.0, 1.0)), None)),
+    );
+    assert!(inner.includes(s1.id));
+    let outer = Csg::new(
+        CsgOperation::Union,
+        Box::new(inner),
+        Box::new(Cube::new(Some(translate(5.0, 0.0, 0.0)), None)),
+    );
+    // the lens between the two spheres spans z from 0 to 1
+    let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+    let xs = outer.intersect(r);
+    assert_eq!(xs.len(), 2);
+    assert_eq!(xs[0].t, 5.0);
+    assert_eq!(xs[1].t, 6.0);
+}
@@ -0,0 +1,78 @@
+use raytracer::matrix::mat4::{scale, translate};
+use raytracer::ray::Ray;
+use raytracer::shape::Cube;
+use raytracer::shape::Shape;
+use raytracer::tuple::{Point, Vector};
+
+#[test]
+fn ray_intersects_cube() {
+    let c = Cube::new(None, None);
+    let cases = vec![
+        (Point::new(5.0, 0.5, 0.0), Vector::new(-1.0, 0.0, 0.0), 4.0, 6.0),
+        (Point::new(-5.0, 0.5, 0.0), Vector::new(1.0, 0.0, 0.0), 4.0, 6.0),
+        (Point::new(0.5, 5.0, 0.0), Vector::new(0.0, -1.0, 0.0), 4.0, 6.0),
+        (Point::new(0.5, -5.0, 0.0), Vector::new(0.0, 1.0, 0.0), 4.0, 6.0),
+        (Point::new(0.5, 0.0, 5.0), Vector::new(0.0, 0.0, -1.0), 4.0, 6.0),
+        (Point::new(0.5, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0), 4.0, 6.0),
+        (Point::new(0.0, 0.5, 0.0), Vector::new(0.0, 0.0, 1.0), -1.0, 1.0),
+    ];
+    for (origin, direction, t1, t2) in cases {
+        let xs = c.intersect(Ray::new(origin, direction));
+        assert_eq!(xs.len(), 2);
+        assert_eq!(xs[0].t, t1);
+        assert_eq!(xs[1].t, t2);
+        assert_eq!(xs[0].object.id(), c.id);
+    }
+}
+
+#[test]
+fn ray_misses_cube() {
+    let c = Cube::new(None, None);
+    let cases = vec![
+        (Point::new(-2.0, 0.0, 0.0), Vector::new(0.2673, 0.5345, 0.8018)),
+        (Point::new(0.0, -2.0, 0.0), Vector::new(0.8018, 0.2673, 0.5345)),
+        (Point::new(0.0, 0.0, -2.0), Vector::new(0.5345, 0.8018, 0.2673)),
+        (Point::new(2.0, 0.0, 2.0), Vector::new(0.0, 0.0, -1.0)),
+        (Point::new(0.0, 2.0, 2.0), Vector::new(0.0, -1.0, 0.0)),
+        (Point::new(2.0, 2.0, 0.0), Vector::new(-1.0, 0.0, 0.0)),
+    ];
+    for (origin, direction) in cases {
+        let xs = c.intersect(Ray::new(origin, direction));
+        assert_eq!(xs.len(), 0);
+    }
+}
+
+#[test]
+fn normal_on_surface_of_cube() {
+    let c = Cube::new(None, None);
+    let cases = vec![
+        (Point::new(1.0, 0.5, -0.8), Vector::new(1.0, 0.0, 0.0)),
+        (Point::new(-1.0, -0.2, 0.9), Vector::new(-1.0, 0.0, 0.0)),
+        (Point::new(-0.4, 1.0, -0.1), Vector::new(0.0, 1.0, 0.0)),
+        (Point::new(0.3, -1.0, -0.7), Vector::new(0.0, -1.0, 0.0)),
+        (Point::new(-0.6, 0.3, 1.0), Vector::new(0.0, 0.0, 1.0)),
+        (Point::new(0.4, 0.4, -1.0), Vector::new(0.0, 0.0, -1.0)),
+        (Point::new(1.0, 1.0, 1.0), Vector::new(1.0, 0.0, 0.0)),
+        (Point::new(-1.0, -1.0, -1.0), Vector::new(-1.0, 0.0, 0.0)),
+    ];
+    for (point, normal) in cases {
+        assert_eq!(c.normal_at(point), normal);
+    }
+}
+
+#[test]
+fn intersecting_transformed_cube() {
+    let c = Cube::new(Some(translate(0.0, 0.0, 5.0).scale(2.0, 2.0, 2.0)), None);
+    let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+    let xs = c.intersect(r);
+    assert_eq!(xs.len(), 2);
+    assert_eq!(xs[0].t, 8.0);
+    assert_eq!(xs[1].t, 12.0);
+}
+
+#[test]
+fn normal_on_scaled_cube() {
+    let c = Cube::new(Some(scale(1.0, 0.5, 1.0)), None);
+    let n = c.normal_at(Point::new(0.5, 0.5, 0.2));
+    assert_eq!(n, Vector::new(0.0, 1.0, 0.0));
+}
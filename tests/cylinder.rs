@@ -0,0 +1,113 @@
+use raytracer::ray::Ray;
+use raytracer::shape::Cylinder;
+use raytracer::shape::Shape;
+use raytracer::tuple::{Point, Vector};
+
+#[test]
+fn ray_misses_cylinder() {
+    let cyl = Cylinder::new(None, None);
+    let cases = vec![
+        (Point::new(1.0, 0.0, 0.0), Vector::new(0.0, 1.0, 0.0)),
+        (Point::new(0.0, 0.0, 0.0), Vector::new(0.0, 1.0, 0.0)),
+        (Point::new(0.0, 0.0, -5.0), Vector::new(1.0, 1.0, 1.0)),
+    ];
+    for (origin, direction) in cases {
+        let xs = cyl.intersect(Ray::new(origin, direction.normalize()));
+        assert_eq!(xs.len(), 0);
+    }
+}
+
+#[test]
+fn ray_strikes_cylinder() {
+    let cyl = Cylinder::new(None, None);
+    let cases = vec![
+        (Point::new(1.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0), 5.0, 5.0),
+        (Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0), 4.0, 6.0),
+        (Point::new(0.5, 0.0, -5.0), Vector::new(0.1, 1.0, 1.0), 6.80798, 7.08872),
+    ];
+    for (origin, direction, t0, t1) in cases {
+        let xs = cyl.intersect(Ray::new(origin, direction.normalize()));
+        assert_eq!(xs.len(), 2);
+        assert!(raytracer::f64eq(xs[0].t, t0));
+        assert!(raytracer::f64eq(xs[1].t, t1));
+    }
+}
+
+#[test]
+fn normal_on_cylinder() {
+    let cyl = Cylinder::new(None, None);
+    let cases = vec![
+        (Point::new(1.0, 0.0, 0.0), Vector::new(1.0, 0.0, 0.0)),
+        (Point::new(0.0, 5.0, -1.0), Vector::new(0.0, 0.0, -1.0)),
+        (Point::new(0.0, -2.0, 1.0), Vector::new(0.0, 0.0, 1.0)),
+        (Point::new(-1.0, 1.0, 0.0), Vector::new(-1.0, 0.0, 0.0)),
+    ];
+    for (point, normal) in cases {
+        assert_eq!(cyl.normal_at(point), normal);
+    }
+}
+
+#[test]
+fn default_cylinder_is_infinite_and_open() {
+    let cyl = Cylinder::new(None, None);
+    assert_eq!(cyl.minimum, f64::NEG_INFINITY);
+    assert_eq!(cyl.maximum, f64::INFINITY);
+    assert!(!cyl.closed);
+}
+
+#[test]
+fn intersecting_constrained_cylinder() {
+    let mut cyl = Cylinder::new(None, None);
+    cyl.minimum = 1.0;
+    cyl.maximum = 2.0;
+    let cases = vec![
+        (Point::new(0.0, 1.5, 0.0), Vector::new(0.1, 1.0, 0.0), 0),
+        (Point::new(0.0, 3.0, -5.0), Vector::new(0.0, 0.0, 1.0), 0),
+        (Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0), 0),
+        (Point::new(0.0, 2.0, -5.0), Vector::new(0.0, 0.0, 1.0), 0),
+        (Point::new(0.0, 1.0, -5.0), Vector::new(0.0, 0.0, 1.0), 0),
+        (Point::new(0.0, 1.5, -2.0), Vector::new(0.0, 0.0, 1.0), 2),
+    ];
+    for (origin, direction, count) in cases {
+        let xs = cyl.intersect(Ray::new(origin, direction.normalize()));
+        assert_eq!(xs.len(), count);
+    }
+}
+
+#[test]
+fn intersecting_caps_of_closed_cylinder() {
+    let mut cyl = Cylinder::new(None, None);
+    cyl.minimum = 1.0;
+    cyl.maximum = 2.0;
+    cyl.closed = true;
+    let cases = vec![
+        (Point::new(0.0, 3.0, 0.0), Vector::new(0.0, -1.0, 0.0), 2),
+        (Point::new(0.0, 3.0, -2.0), Vector::new(0.0, -1.0, 2.0), 2),
+        (Point::new(0.0, 4.0, -2.0), Vector::new(0.0, -1.0, 1.0), 2),
+        (Point::new(0.0, 0.0, -2.0), Vector::new(0.0, 1.0, 2.0), 2),
+        (Point::new(0.0, -1.0, -2.0), Vector::new(0.0, 1.0, 1.0), 2),
+    ];
+    for (origin, direction, count) in cases {
+        let xs = cyl.intersect(Ray::new(origin, direction.normalize()));
+        assert_eq!(xs.len(), count);
+    }
+}
+
+#[test]
+fn normal_on_cylinder_end_caps() {
+    let mut cyl = Cylinder::new(None, None);
+    cyl.minimum = 1.0;
+    cyl.maximum = 2.0;
+    cyl.closed = true;
+    let cases = vec![
+        (Point::new(0.0, 1.0, 0.0), Vector::new(0.0, -1.0, 0.0)),
+        (Point::new(0.5, 1.0, 0.0), Vector::new(0.0, -1.0, 0.0)),
+        (Point::new(0.0, 1.0, 0.5), Vector::new(0.0, -1.0, 0.0)),
+        (Point::new(0.0, 2.0, 0.0), Vector::new(0.0, 1.0, 0.0)),
+        (Point::new(0.5, 2.0, 0.0), Vector::new(0.0, 1.0, 0.0)),
+        (Point::new(0.0, 2.0, 0.5), Vector::new(0.0, 1.0, 0.0)),
+    ];
+    for (point, normal) in cases {
+        assert_eq!(cyl.normal_at(point), normal);
+    }
+}
@@ -0,0 +1,115 @@
+use std::f64::consts::PI;
+
+use raytracer::intersection::Intersection;
+use raytracer::material::Material;
+use raytracer::matrix::mat4::{identity, rotate_y, scale, translate};
+use raytracer::ray::Ray;
+use raytracer::shape::{Group, Shape, Sphere};
+use raytracer::tuple::{Point, Vector};
+
+#[test]
+fn creating_new_group() {
+    let g = Group::new(None);
+    assert_eq!(g.transform(), identity());
+    assert!(g.is_empty());
+}
+
+#[test]
+fn adding_child_to_group() {
+    let mut g = Group::new(Some(translate(1.0, 2.0, 3.0)));
+    let s = Sphere::new(None, None);
+    g.add_child(s.clone_box());
+    assert_eq!(g.children().len(), 1);
+    assert_eq!(g.children()[0].id(), s.id);
+    assert_eq!(g.children()[0].parent_transform(), translate(1.0, 2.0, 3.0));
+}
+
+#[test]
+fn intersecting_ray_with_empty_group() {
+    let g = Group::new(None);
+    let r = Ray::new(Point::new(0.0, 0.0, 0.0), Vector::new(0.0, 0.0, 1.0));
+    assert_eq!(g.intersect(r).len(), 0);
+}
+
+#[test]
+fn intersecting_ray_with_nonempty_group() {
+    let mut g = Group::new(None);
+    let s1 = Sphere::new(None, None);
+    let s2 = Sphere::new(Some(translate(0.0, 0.0, -3.0)), None);
+    let s3 = Sphere::new(Some(translate(5.0, 0.0, 0.0)), None);
+    g.add_children(vec![s1.clone_box(), s2.clone_box(), s3.clone_box()]);
+    let r = Ray::new(Point::new(0.0, 0.0, -5.0), Vector::new(0.0, 0.0, 1.0));
+    let xs = g.intersect(r);
+    assert_eq!(xs.len(), 4);
+    assert_eq!(xs[0].object.id(), s2.id);
+    assert_eq!(xs[1].object.id(), s2.id);
+    assert_eq!(xs[2].object.id(), s1.id);
+    assert_eq!(xs[3].object.id(), s1.id);
+}
+
+#[test]
+fn intersecting_transformed_group() {
+    let mut g = Group::new(Some(scale(2.0, 2.0, 2.0)));
+    g.add_child(Box::new(Sphere::new(Some(translate(5.0, 0.0, 0.0)), None)));
+    let r = Ray::new(Point::new(10.0, 0.0, -10.0), Vector::new(0.0, 0.0, 1.0));
+    assert_eq!(g.intersect(r).len(), 2);
+}
+
+fn nested_groups(g2_scale: (f64, f64, f64)) -> (Group, Sphere) {
+    let s = Sphere::new(Some(translate(5.0, 0.0, 0.0)), None);
+    let mut g2 = Group::new(Some(scale(g2_scale.0, g2_scale.1, g2_scale.2)));
+    g2.add_child(s.clone_box());
+    let mut g1 = Group::new(Some(rotate_y(PI / 2.0)));
+    g1.add_child(Box::new(g2));
+    (g1, s)
+}
+
+fn nested_sphere(g1: &Group) -> &dyn Shape {
+    let g2 = g1.children()[0].as_any().downcast_ref::<Group>().unwrap();
+    g2.children()[0].as_ref()
+}
+
+#[test]
+fn converting_point_from_world_to_object_space() {
+    let (g1, _) = nested_groups((2.0, 2.0, 2.0));
+    let p = nested_sphere(&g1).world_to_object(Point::new(-2.0, 0.0, -10.0));
+    assert_eq!(p, Point::new(0.0, 0.0, -1.0));
+}
+
+#[test]
+fn converting_normal_from_object_to_world_space() {
+    let (g1, _) = nested_groups((1.0, 2.0, 3.0));
+    let v = 3f64.sqrt() / 3.0;
+    let n = nested_sphere(&g1).normal_to_world(Vector::new(v, v, v));
+    assert_eq!(n, Vector::new(0.28571, 0.42857, -0.85714));
+}
+
+#[test]
+fn finding_normal_on_child_object() {
+    let (g1, _) = nested_groups((1.0, 2.0, 3.0));
+    let n = nested_sphere(&g1).normal_at(Point::new(1.7321, 1.1547, -5.5774));
+    assert_eq!(n, Vector::new(0.28570, 0.42854, -0.85716));
+}
+
+#[test]
+fn transforming_group_moves_its_children() {
+    let (mut g1, s) = nested_groups((2.0, 2.0, 2.0));
+    g1.set_transform(identity());
+    // without the rotation the sphere sits at x=10 with radius 2
+    let r = Ray::new(Point::new(10.0, 0.0, -10.0), Vector::new(0.0, 0.0, 1.0));
+    let xs = g1.intersect(r);
+    assert_eq!(xs.len(), 2);
+    assert_eq!(xs[0].t, 8.0);
+    let comps = Intersection::new(xs[0].t, xs[0].object.clone()).prepare_computation(r);
+    assert_eq!(comps.object.id(), s.id);
+    assert_eq!(comps.normalv, Vector::new(0.0, 0.0, -1.0));
+}
+
+#[test]
+fn group_material_is_applied_to_children() {
+    let mut g = Group::new(None);
+    g.add_child(Box::new(Sphere::new(None, None)));
+    let m = Material { ambient: 1.0, ..Material::default() };
+    g.set_material(m);
+    assert_eq!(g.children()[0].material().ambient, 1.0);
+}
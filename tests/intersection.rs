@@ -1,4 +1,4 @@
-use raytracer::{EPSILON, intersection::{Intersection, Intersections}, matrix::mat4::translate, ray::Ray, shape::Shape, shape::Sphere, tuple::*};
+use raytracer::{EPSILON, intersection::{Intersection, Intersections}, material::Material, matrix::mat4::{scale, translate}, ray::Ray, shape::Shape, shape::{Plane, Sphere}, tuple::*};
 
 #[test]
 fn create_intersection() {
@@ -110,4 +110,84 @@ fn hit_should_offset_the_point() {
     let c = i.prepare_computation(r);
     assert!(c.over_point.z < -EPSILON/2.0);
     assert!(c.point.z > c.over_point.z);
-}
\ No newline at end of file
+}
+
+#[test]
+fn precomputing_reflection_vector() {
+    let s = Plane::new(None, None);
+    let r = Ray::new(Point::new(0., 1., -1.), Vector::new(0., -(2f64.sqrt()) / 2., 2f64.sqrt() / 2.));
+    let i = Intersection::new(2f64.sqrt(), s.clone_box());
+    let c = i.prepare_computation(r);
+    assert_eq!(c.reflectv, Vector::new(0., 2f64.sqrt() / 2., 2f64.sqrt() / 2.));
+}
+
+fn glass_sphere(transform: Option<raytracer::matrix::Mat4>, refractive_index: f64) -> Sphere {
+    let m = Material { refractive_index, ..Material::glass() };
+    Sphere::new(transform, Some(m))
+}
+
+#[test]
+fn finding_n1_and_n2_at_various_intersections() {
+    let a = glass_sphere(Some(scale(2., 2., 2.)), 1.5);
+    let b = glass_sphere(Some(translate(0., 0., -0.25)), 2.0);
+    let c = glass_sphere(Some(translate(0., 0., 0.25)), 2.5);
+    let r = Ray::new(Point::new(0., 0., -4.), Vector::new(0., 0., 1.));
+    let xs = Intersections::new(vec![
+        Intersection::new(2.0, a.clone_box()),
+        Intersection::new(2.75, b.clone_box()),
+        Intersection::new(3.25, c.clone_box()),
+        Intersection::new(4.75, b.clone_box()),
+        Intersection::new(5.25, c.clone_box()),
+        Intersection::new(6.0, a.clone_box()),
+    ]);
+    let expected = [(1.0, 1.5), (1.5, 2.0), (2.0, 2.5), (2.5, 2.5), (2.5, 1.5), (1.5, 1.0)];
+    for (idx, (n1, n2)) in expected.iter().enumerate() {
+        let comps = xs[idx].prepare_computation_with(r, &xs);
+        assert_eq!(comps.n1, *n1);
+        assert_eq!(comps.n2, *n2);
+    }
+}
+
+#[test]
+fn under_point_is_offset_below_the_surface() {
+    let r = Ray::new(Point::new(0., 0., -5.), Vector::new(0., 0., 1.));
+    let s = glass_sphere(Some(translate(0., 0., 1.)), 1.5);
+    let i = Intersection::new(5.0, s.clone_box());
+    let xs = Intersections::new(vec![i.clone()]);
+    let c = i.prepare_computation_with(r, &xs);
+    assert!(c.under_point.z > EPSILON / 2.0);
+    assert!(c.point.z < c.under_point.z);
+}
+
+#[test]
+fn schlick_under_total_internal_reflection() {
+    let s = glass_sphere(None, 1.5);
+    let r = Ray::new(Point::new(0., 0., 2f64.sqrt() / 2.), Vector::new(0., 1., 0.));
+    let xs = Intersections::new(vec![
+        Intersection::new(-(2f64.sqrt()) / 2., s.clone_box()),
+        Intersection::new(2f64.sqrt() / 2., s.clone_box()),
+    ]);
+    let comps = xs[1].prepare_computation_with(r, &xs);
+    assert_eq!(comps.schlick(), 1.0);
+}
+
+#[test]
+fn schlick_with_perpendicular_viewing_angle() {
+    let s = glass_sphere(None, 1.5);
+    let r = Ray::new(Point::new(0., 0., 0.), Vector::new(0., 1., 0.));
+    let xs = Intersections::new(vec![
+        Intersection::new(-1.0, s.clone_box()),
+        Intersection::new(1.0, s.clone_box()),
+    ]);
+    let comps = xs[1].prepare_computation_with(r, &xs);
+    assert!((comps.schlick() - 0.04).abs() < EPSILON);
+}
+
+#[test]
+fn schlick_with_small_angle_and_n2_greater_than_n1() {
+    let s = glass_sphere(None, 1.5);
+    let r = Ray::new(Point::new(0., 0.99, -2.), Vector::new(0., 0., 1.));
+    let xs = Intersections::new(vec![Intersection::new(1.8589, s.clone_box())]);
+    let comps = xs[0].prepare_computation_with(r, &xs);
+    assert!((comps.schlick() - 0.48873).abs() < 0.0001);
+}
@@ -1,10 +1,324 @@
-use raytracer::color::Color; 
-use raytracer::light::Light;
-use raytracer::tuple::Point;
+use raytracer::color::{Color, WHITE};
+use raytracer::light::{AreaLight, Attenuation, DirectionalLight, Light, LightSource, SpotLight};
+use raytracer::material::Material;
+use raytracer::matrix::mat4::translate;
+use raytracer::shape::{Plane, Shape, Sphere};
+use raytracer::tuple::{Point, Vector};
+use raytracer::ray::Ray;
+use raytracer::world::World;
+use std::f64::consts::{FRAC_1_SQRT_2, PI};
 
 #[test]
 fn point_light() {
     let l = Light::new(Point::new(0., 0., 0.), Color::new(1., 1., 1.));
     assert_eq!(l.position, Point::new(0., 0., 0.));
     assert_eq!(l.intensity, Color::new(1., 1., 1.));
-}
\ No newline at end of file
+    let samples = l.samples(Point::new(3., 2., 1.));
+    assert_eq!(samples.len(), 1);
+    assert_eq!(samples[0].distance, 14f64.sqrt());
+}
+
+#[test]
+fn point_lights_evaluate_light_intensity_at_given_point() {
+    let w = World::default();
+    let light = w.lights[0].clone();
+    let cases = [
+        (Point::new(0., 1.0001, 0.), 1.0),
+        (Point::new(-1.0001, 0., 0.), 1.0),
+        (Point::new(0., 0., -1.0001), 1.0),
+        (Point::new(0., 0., 1.0001), 0.0),
+        (Point::new(1.0001, 0., 0.), 0.0),
+        (Point::new(0., -1.0001, 0.), 0.0),
+        (Point::new(0., 0., 0.), 0.0),
+    ];
+    for (p, intensity) in cases {
+        assert_eq!(w.intensity_at(light.as_ref(), p), intensity);
+    }
+}
+
+#[test]
+fn lighting_uses_light_intensity_to_attenuate_color() {
+    let light = Light::new(Point::new(0., 0., -10.), WHITE);
+    let m = Material { ambient: 0.1, diffuse: 0.9, specular: 0.0, ..Material::default() };
+    let s = Sphere::new(None, Some(m.clone()));
+    let p = Point::new(0., 0., -1.);
+    let eyev = Vector::new(0., 0., -1.);
+    let normalv = Vector::new(0., 0., -1.);
+    assert_eq!(m.lighting(&s, &light, p, eyev, normalv, 1.0), Color::new(1., 1., 1.));
+    assert_eq!(m.lighting(&s, &light, p, eyev, normalv, 0.5), Color::new(0.55, 0.55, 0.55));
+    assert_eq!(m.lighting(&s, &light, p, eyev, normalv, 0.0), Color::new(0.1, 0.1, 0.1));
+}
+
+#[test]
+fn creating_area_light() {
+    let corner = Point::new(0., 0., 0.);
+    let light = AreaLight::with_samples(corner, Vector::new(2., 0., 0.), Vector::new(0., 0., 1.), 8, WHITE);
+    assert_eq!(light.corner, corner);
+    assert_eq!(light.uvec, Vector::new(1., 0., 0.));
+    assert_eq!(light.usteps, 2);
+    assert_eq!(light.vvec, Vector::new(0., 0., 0.25));
+    assert_eq!(light.vsteps, 4);
+    assert_eq!(light.sample_count(), 8);
+    assert_eq!(light.position(), Point::new(1., 0., 0.5));
+}
+
+#[test]
+fn finding_single_point_on_area_light() {
+    let corner = Point::new(0., 0., 0.);
+    let mut light = AreaLight::with_samples(corner, Vector::new(2., 0., 0.), Vector::new(0., 0., 1.), 16, WHITE);
+    light.jitter = false;
+    let lit = Point::new(0., 5., 0.);
+    assert_eq!(light.point_on_light(0, 0, lit), Point::new(0.25, 0., 0.125));
+    assert_eq!(light.point_on_light(1, 0, lit), Point::new(0.75, 0., 0.125));
+    assert_eq!(light.point_on_light(0, 1, lit), Point::new(0.25, 0., 0.375));
+    assert_eq!(light.point_on_light(2, 0, lit), Point::new(1.25, 0., 0.125));
+    assert_eq!(light.point_on_light(3, 1, lit), Point::new(1.75, 0., 0.375));
+}
+
+#[test]
+fn jittered_points_stay_in_their_cell_and_are_repeatable() {
+    let corner = Point::new(0., 0., 0.);
+    let light = AreaLight::with_samples(corner, Vector::new(2., 0., 0.), Vector::new(0., 0., 1.), 16, WHITE);
+    let lit = Point::new(0.3, 5., -0.7);
+    let samples = light.sample_points(lit);
+    assert_eq!(samples.len(), 16);
+    assert_eq!(samples, light.sample_points(lit));
+    assert_ne!(samples, light.sample_points(Point::new(0.3, 5., -0.8)));
+    for (i, p) in samples.iter().enumerate() {
+        let (u, v) = ((i % 4) as f64, (i / 4) as f64);
+        assert!(p.x >= u * 0.5 && p.x <= (u + 1.) * 0.5);
+        assert!(p.z >= v * 0.25 && p.z <= (v + 1.) * 0.25);
+    }
+}
+
+#[test]
+fn area_light_intensity_function() {
+    let w = World::default();
+    let corner = Point::new(-0.5, -0.5, -5.);
+    let mut light = AreaLight::with_samples(corner, Vector::new(1., 0., 0.), Vector::new(0., 1., 0.), 4, WHITE);
+    light.jitter = false;
+    let cases = [
+        (Point::new(0., 0., 2.), 0.0),
+        (Point::new(1., -1., 2.), 0.25),
+        (Point::new(1.5, 0., 2.), 0.5),
+        (Point::new(1.25, 1.25, 3.), 0.75),
+        (Point::new(0., 0., -2.), 1.0),
+    ];
+    for (p, intensity) in cases {
+        assert_eq!(w.intensity_at(&light, p), intensity);
+    }
+}
+
+#[test]
+fn lighting_samples_the_area_light() {
+    let corner = Point::new(-0.5, -0.5, -5.);
+    let mut light = AreaLight::with_samples(corner, Vector::new(1., 0., 0.), Vector::new(0., 1., 0.), 4, WHITE);
+    light.jitter = false;
+    let m = Material { ambient: 0.1, diffuse: 0.9, specular: 0.0, ..Material::default() };
+    let s = Sphere::new(None, Some(m));
+    let eye = Point::new(0., 0., -5.);
+    let cases = [
+        (Point::new(0., 0., -1.), Color::new(0.9965, 0.9965, 0.9965)),
+        (Point::new(0., FRAC_1_SQRT_2, -FRAC_1_SQRT_2), Color::new(0.62318, 0.62318, 0.62318)),
+    ];
+    for (p, expected) in cases {
+        let eyev = (eye - p).normalize();
+        let normalv = Vector::new(p.x, p.y, p.z);
+        let c = s.material().lighting(&s, &light, p, eyev, normalv, 1.0);
+        assert!((c.r - expected.r).abs() < 0.0001, "{:?} != {:?}", c, expected);
+    }
+}
+
+#[test]
+fn shadows_and_shading_use_the_same_light_samples() {
+    // a ball over a floor, under a jittered area light
+    let floor = Plane::new(Some(translate(0., -1., 0.)), None);
+    let ball = Sphere::new(None, None);
+    let light = AreaLight::with_samples(Point::new(-1., 5., -1.), Vector::new(2., 0., 0.), Vector::new(0., 0., 2.), 16, WHITE);
+    let w = World::new(Some(vec![Box::new(light)]), Some(vec![floor.clone_box(), ball.clone_box()]));
+    // a point of the floor in the penumbra of the ball
+    let r = Ray::new(Point::new(1.2, 5., 0.), Vector::new(0., -1., 0.));
+    let xs = w.intersect(r);
+    let hit = xs.hit().unwrap();
+    let c = hit.prepare_computation_with(r, &xs);
+    let samples = w.lights[0].attenuated_samples(c.point);
+    let visible = w.visible_fraction(c.over_point, &samples);
+    assert!(visible > 0. && visible < 1.);
+    let m = c.object.material();
+    let expected = m.ambient_at(c.object.as_ref(), c.point, w.ambient_light())
+        + m.direct_lighting(c.object.as_ref(), &samples, c.point, c.eyev, c.normalv, visible);
+    assert_eq!(w.shade_hit(hit.prepare_computation_with(r, &xs)), expected);
+}
+
+#[test]
+fn attenuated_samples_fade_with_distance() {
+    let mut light = Light::new(Point::new(0., 0., 0.), WHITE);
+    light.attenuation = Attenuation::Linear(10.);
+    let samples = light.attenuated_samples(Point::new(0., 5., 0.));
+    assert_eq!(samples.len(), 1);
+    assert_eq!(samples[0].intensity, Color::new(0.5, 0.5, 0.5));
+    assert_eq!(samples[0].distance, 5.);
+}
+
+#[test]
+fn point_light_has_a_single_sample_toward_it() {
+    let light = Light::new(Point::new(0., 10., 0.), WHITE);
+    let samples = light.samples(Point::new(0., 0., 0.));
+    assert_eq!(samples.len(), 1);
+    assert_eq!(samples[0].direction, Vector::new(0., 1., 0.));
+    assert_eq!(samples[0].distance, 10.);
+    assert_eq!(samples[0].intensity, WHITE);
+}
+
+#[test]
+fn spot_light_fades_between_its_cones() {
+    let light = SpotLight::new(Point::new(0., 0., 0.), Vector::new(0., -2., 0.), PI / 6., PI / 3., WHITE);
+    assert_eq!(light.direction, Vector::new(0., -1., 0.));
+    // inside the inner cone
+    assert_eq!(light.falloff(Point::new(0., -5., 0.)), 1.0);
+    assert_eq!(light.falloff(Point::new(1., -5., 0.)), 1.0);
+    // outside the outer cone, and behind the light
+    assert_eq!(light.falloff(Point::new(5., -1., 0.)), 0.0);
+    assert_eq!(light.falloff(Point::new(0., 5., 0.)), 0.0);
+    // halfway between the two cones
+    let cos = ((PI / 6.).cos() + (PI / 3.).cos()) / 2.;
+    let p = Point::new((1. - cos * cos).sqrt(), -cos, 0.);
+    assert!((light.falloff(p) - 0.5).abs() < 0.0001);
+}
+
+#[test]
+fn spot_light_sample_carries_the_falloff() {
+    let light = SpotLight::new(Point::new(0., 5., 0.), Vector::new(0., -1., 0.), PI / 6., PI / 3., WHITE);
+    let lit = light.samples(Point::new(0., 0., 0.));
+    assert_eq!(lit.len(), 1);
+    assert_eq!(lit[0].direction, Vector::new(0., 1., 0.));
+    assert_eq!(lit[0].distance, 5.);
+    assert_eq!(lit[0].intensity, WHITE);
+    let dark = light.samples(Point::new(10., 0., 0.));
+    assert_eq!(dark[0].intensity, Color::new(0., 0., 0.));
+}
+
+#[test]
+fn directional_light_is_the_same_everywhere() {
+    let light = DirectionalLight::new(Vector::new(0., -3., 0.), WHITE);
+    for p in [Point::new(0., 0., 0.), Point::new(100., -50., 3.)] {
+        let samples = light.samples(p);
+        assert_eq!(samples.len(), 1);
+        assert_eq!(samples[0].direction, Vector::new(0., 1., 0.));
+        assert_eq!(samples[0].distance, f64::INFINITY);
+        assert_eq!(samples[0].intensity, WHITE);
+    }
+}
+
+#[test]
+fn directional_light_shadows_have_no_end() {
+    let w = World::default();
+    let light = DirectionalLight::new(Vector::new(0., -1., 0.), WHITE);
+    // however far below the spheres, the point is in their shadow
+    assert_eq!(w.intensity_at(&light, Point::new(0., -2., 0.)), 0.0);
+    assert_eq!(w.intensity_at(&light, Point::new(0., -1000., 0.)), 0.0);
+    assert_eq!(w.intensity_at(&light, Point::new(0., 2., 0.)), 1.0);
+    assert_eq!(w.intensity_at(&light, Point::new(2., -2., 0.)), 1.0);
+}
+
+#[test]
+fn spot_light_only_lights_inside_its_cone() {
+    let mut w = World::default();
+    w.lights = vec![Box::new(SpotLight::new(
+        Point::new(0., 0., -10.), Vector::new(0., 0., 1.), PI / 18., PI / 12., WHITE))];
+    let r = Ray::new(Point::new(0., 0., -5.), Vector::new(0., 0., 1.));
+    let lit = w.color_at(r);
+    // the same spot pointing away leaves only the ambient
+    w.lights = vec![Box::new(SpotLight::new(
+        Point::new(0., 0., -10.), Vector::new(0., 0., -1.), PI / 18., PI / 12., WHITE))];
+    let dark = w.color_at(r);
+    assert_eq!(dark, Color::new(0.08, 0.1, 0.06));
+    assert!(lit.g > dark.g);
+}
+
+#[test]
+fn world_mixes_all_kinds_of_lights() {
+    let lights: Vec<Box<dyn LightSource>> = vec![
+        Box::new(Light::new(Point::new(-10., 10., -10.), WHITE)),
+        Box::new(SpotLight::new(Point::new(0., 0., -10.), Vector::new(0., 0., 1.), PI / 18., PI / 12., WHITE)),
+        Box::new(DirectionalLight::new(Vector::new(1., -1., 1.), WHITE)),
+    ];
+    let r = Ray::new(Point::new(0., 0., -5.), Vector::new(0., 0., 1.));
+    let mut w = World::default();
+    // the ambient light once, then what each light adds to it
+    w.lights.clear();
+    let ambient = w.color_at(r);
+    let mut expected = ambient;
+    for light in &lights {
+        w.lights = vec![light.clone()];
+        expected = expected + (w.color_at(r) - ambient);
+    }
+    w.lights = lights;
+    assert_eq!(w.color_at(r), expected);
+}
+
+#[test]
+fn attenuation_models() {
+    assert_eq!(Attenuation::None.factor(1000.), 1.0);
+    assert_eq!(Attenuation::None.factor(f64::INFINITY), 1.0);
+    assert_eq!(Attenuation::Linear(10.).factor(0.), 1.0);
+    assert_eq!(Attenuation::Linear(10.).factor(5.), 0.5);
+    assert_eq!(Attenuation::Linear(10.).factor(20.), 0.0);
+    assert_eq!(Attenuation::InverseSquare(1.).factor(0.), 1.0);
+    assert_eq!(Attenuation::InverseSquare(1.).factor(1.), 0.25);
+    assert_eq!(Attenuation::InverseSquare(1.).factor(3.), 0.0625);
+    assert_eq!(Attenuation::InverseSquare(2.).factor(f64::INFINITY), 0.0);
+}
+
+#[test]
+fn attenuation_needs_a_positive_range_or_radius() {
+    assert_eq!(Attenuation::linear(10.), Ok(Attenuation::Linear(10.)));
+    assert_eq!(Attenuation::inverse_square(0.5), Ok(Attenuation::InverseSquare(0.5)));
+    for bad in [0.0, -1.0, f64::NAN, f64::INFINITY] {
+        assert!(Attenuation::linear(bad).is_err());
+        assert!(Attenuation::inverse_square(bad).is_err());
+    }
+}
+
+#[test]
+fn lights_do_not_attenuate_by_default() {
+    let light = Light::new(Point::new(0., 0., 0.), WHITE);
+    assert_eq!(light.attenuation, Attenuation::None);
+    let spot = SpotLight::new(Point::new(0., 0., 0.), Vector::new(0., 0., 1.), PI / 6., PI / 3., WHITE);
+    assert_eq!(spot.attenuation(), Attenuation::None);
+    let sun = DirectionalLight::new(Vector::new(0., -1., 0.), WHITE);
+    assert_eq!(sun.attenuation(), Attenuation::None);
+}
+
+#[test]
+fn lighting_fades_diffuse_and_specular_with_distance() {
+    let m = Material { ambient: 0.1, diffuse: 0.9, specular: 0.0, ..Material::default() };
+    let s = Sphere::new(None, Some(m.clone()));
+    let p = Point::new(0., 0., 0.);
+    let eyev = Vector::new(0., 0., -1.);
+    let normalv = Vector::new(0., 0., -1.);
+    let mut light = Light::new(Point::new(0., 0., -10.), WHITE);
+    let cases = [
+        (Attenuation::None, Color::new(1., 1., 1.)),
+        (Attenuation::Linear(20.), Color::new(0.55, 0.55, 0.55)),
+        (Attenuation::Linear(5.), Color::new(0.1, 0.1, 0.1)),
+        (Attenuation::InverseSquare(10.), Color::new(0.325, 0.325, 0.325)),
+    ];
+    for (attenuation, expected) in cases {
+        light.attenuation = attenuation;
+        assert_eq!(m.lighting(&s, &light, p, eyev, normalv, 1.0), expected);
+    }
+}
+
+#[test]
+fn attenuated_specular_highlight() {
+    let m = Material::default();
+    let s = Sphere::new(None, None);
+    let p = Point::new(0., 0., 0.);
+    let eyev = Vector::new(0., 0., -1.);
+    let normalv = Vector::new(0., 0., -1.);
+    let mut light = Light::new(Point::new(0., 0., -10.), WHITE);
+    light.attenuation = Attenuation::Linear(20.);
+    // ambient 0.1, plus half of diffuse 0.9 and specular 0.9
+    assert_eq!(m.lighting(&s, &light, p, eyev, normalv, 1.0), Color::new(1.0, 1.0, 1.0));
+}
@@ -1,6 +1,7 @@
 use raytracer::color::Color;
 use raytracer::light::Light;
 use raytracer::material::Material;
+use raytracer::shape::Sphere;
 use raytracer::tuple::*;
 
 #[test]
@@ -11,6 +12,16 @@ fn default_material() {
     assert_eq!(m.diffuse, 0.9);
     assert_eq!(m.specular, 0.9);
     assert_eq!(m.shininess, 200.0);
+    assert_eq!(m.reflective, 0.0);
+    assert_eq!(m.transparency, 0.0);
+    assert_eq!(m.refractive_index, 1.0);
+}
+
+#[test]
+fn glass_material() {
+    let m = Material::glass();
+    assert_eq!(m.transparency, 1.0);
+    assert_eq!(m.refractive_index, 1.5);
 }
 
 #[test]
@@ -20,7 +31,7 @@ fn lighting_eye_between_light_and_surface() {
     let eyev = Vector::new(0., 0., -1.);
     let normalv = Vector::new(0., 0., -1.);
     let light = Light::new(Point::new(0., 0., -10.), Color::new(1., 1.,1.));
-    let result = m.lighting(light, position, eyev, normalv, false);
+    let result = m.lighting(&Sphere::new(None, None), &light, position, eyev, normalv, 1.0);
     assert_eq!(result, Color::new(1.9, 1.9, 1.9));
 }
 
@@ -31,7 +42,7 @@ fn lighting_eye_between_light_and_surface_eye_45ofset() {
     let eyev = Vector::new(0., 2f64.sqrt()/2.0, 2f64.sqrt()/2.0);
     let normalv = Vector::new(0., 0., -1.);
     let light = Light::new(Point::new(0., 0., -10.), Color::new(1., 1.,1.));
-    let result = m.lighting(light, position, eyev, normalv, false);
+    let result = m.lighting(&Sphere::new(None, None), &light, position, eyev, normalv, 1.0);
     assert_eq!(result, Color::new(1., 1., 1.));
 }
 
@@ -42,7 +53,7 @@ fn lighting_eye_opposite_surface_light_45ofset() {
     let eyev = Vector::new(0., 0., -1.);
     let normalv = Vector::new(0., 0., -1.);
     let light = Light::new(Point::new(0., 10., -10.), Color::new(1., 1.,1.));
-    let result = m.lighting(light, position, eyev, normalv, false);
+    let result = m.lighting(&Sphere::new(None, None), &light, position, eyev, normalv, 1.0);
     assert_eq!(result, Color::new(0.7364, 0.7364, 0.7364));
 }
 
@@ -53,7 +64,7 @@ fn lighting_eye_in_path_reflectio_vector() {
     let eyev = Vector::new(0., -2f64.sqrt()/2.0, -2f64.sqrt()/2.0);
     let normalv = Vector::new(0., 0., -1.);
     let light = Light::new(Point::new(0., 10., -10.), Color::new(1., 1.,1.));
-    let result = m.lighting(light, position, eyev, normalv, false);
+    let result = m.lighting(&Sphere::new(None, None), &light, position, eyev, normalv, 1.0);
     let c = Color::new(1.6364, 1.6364, 1.6364);
     assert_eq!(result, c);
 }
@@ -65,7 +76,7 @@ fn lighting_light_behind_surface() {
     let eyev = Vector::new(0., 0., -1.);
     let normalv = Vector::new(0., 0., -1.);
     let light = Light::new(Point::new(0., 0., 10.), Color::new(1., 1.,1.));
-    let result = m.lighting(light, position, eyev, normalv, false);
+    let result = m.lighting(&Sphere::new(None, None), &light, position, eyev, normalv, 1.0);
     assert_eq!(result, Color::new(0.1, 0.1, 0.1));
 }
 
@@ -76,6 +87,6 @@ fn lighting_with_surface_in_shadow() {
     let eyev = Vector::new(0., 0., -1.);
     let normalv = Vector::new(0., 0., -1.);
     let light = Light::new(Point::new(0., 0., -10.), Color::new(1., 1.,1.));
-    let result = m.lighting(light, position, eyev, normalv, true);
+    let result = m.lighting(&Sphere::new(None, None), &light, position, eyev, normalv, 0.0);
     assert_eq!(result, Color::new(0.1, 0.1, 0.1));
 }
\ No newline at end of file
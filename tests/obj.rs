@@ -0,0 +1,177 @@
+use raytracer::obj::{parse_obj, ObjParser};
+use raytracer::shape::{Group, Shape, SmoothTriangle, Triangle};
+use raytracer::tuple::{Point, Vector};
+
+fn as_triangle(shape: &dyn Shape) -> &Triangle {
+    shape.as_any().downcast_ref::<Triangle>().unwrap()
+}
+
+#[test]
+fn ignoring_unrecognized_lines() {
+    let gibberish = "There was a young lady named Bright
+who traveled much faster than light.
+She set out one day
+in a relative way,
+and came back the previous night.";
+    let parser = parse_obj(gibberish);
+    assert_eq!(parser.ignored.len(), 5);
+    assert_eq!(parser.ignored[0].line, 1);
+    assert_eq!(parser.ignored[4].line, 5);
+}
+
+#[test]
+fn comments_and_blank_lines_are_not_reported() {
+    let parser = parse_obj("# a comment\n\n   \nv 1 2 3\n");
+    assert_eq!(parser.ignored.len(), 0);
+    assert_eq!(parser.vertices.len(), 1);
+}
+
+#[test]
+fn vertex_records() {
+    let file = "v -1 1 0
+v -1.0000 0.5000 0.0000
+v 1 0 0
+v 1 1 0";
+    let parser = parse_obj(file);
+    assert_eq!(parser.vertices[0], Point::new(-1.0, 1.0, 0.0));
+    assert_eq!(parser.vertices[1], Point::new(-1.0, 0.5, 0.0));
+    assert_eq!(parser.vertices[2], Point::new(1.0, 0.0, 0.0));
+    assert_eq!(parser.vertices[3], Point::new(1.0, 1.0, 0.0));
+}
+
+#[test]
+fn malformed_lines_are_reported_with_line_number() {
+    let file = "v 1 2
+v a b c
+v 0 0 0
+v 1 0 0
+v 0 1 0
+f 1 2 9
+f 1 2
+f 1 2 3";
+    let parser = parse_obj(file);
+    let lines = parser.ignored.iter().map(|i| i.line).collect::<Vec<usize>>();
+    assert_eq!(lines, vec![1, 2, 6, 7]);
+    assert_eq!(parser.vertices.len(), 3);
+    assert_eq!(parser.default_group.len(), 1);
+}
+
+#[test]
+fn parsing_triangle_faces() {
+    let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+
+f 1 2 3
+f 1 3 4";
+    let parser = parse_obj(file);
+    let t1 = as_triangle(parser.default_group[0].as_ref());
+    let t2 = as_triangle(parser.default_group[1].as_ref());
+    assert_eq!(t1.p1, parser.vertices[0]);
+    assert_eq!(t1.p2, parser.vertices[1]);
+    assert_eq!(t1.p3, parser.vertices[2]);
+    assert_eq!(t2.p1, parser.vertices[0]);
+    assert_eq!(t2.p2, parser.vertices[2]);
+    assert_eq!(t2.p3, parser.vertices[3]);
+}
+
+#[test]
+fn triangulating_polygons() {
+    let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+v 0 2 0
+
+f 1 2 3 4 5";
+    let parser = parse_obj(file);
+    assert_eq!(parser.default_group.len(), 3);
+    let t3 = as_triangle(parser.default_group[2].as_ref());
+    assert_eq!(t3.p1, parser.vertices[0]);
+    assert_eq!(t3.p2, parser.vertices[3]);
+    assert_eq!(t3.p3, parser.vertices[4]);
+}
+
+#[test]
+fn triangles_in_groups() {
+    let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+
+g FirstGroup
+f 1 2 3
+g SecondGroup
+f 1 3 4";
+    let parser = parse_obj(file);
+    assert_eq!(parser.default_group.len(), 0);
+    let t1 = as_triangle(parser.group("FirstGroup").unwrap()[0].as_ref());
+    let t2 = as_triangle(parser.group("SecondGroup").unwrap()[0].as_ref());
+    assert_eq!(t1.p3, parser.vertices[2]);
+    assert_eq!(t2.p3, parser.vertices[3]);
+    assert_eq!(parser.into_shapes().len(), 2);
+}
+
+#[test]
+fn vertex_normal_and_texture_records() {
+    let file = "vn 0 0 1
+vn 0.707 0 -0.707
+vn 1 2 3
+vt 0.5 0.25";
+    let parser = parse_obj(file);
+    assert_eq!(parser.normals[0], Vector::new(0.0, 0.0, 1.0));
+    assert_eq!(parser.normals[1], Vector::new(0.707, 0.0, -0.707));
+    assert_eq!(parser.normals[2], Vector::new(1.0, 2.0, 3.0));
+    assert_eq!(parser.texture_coords[0], (0.5, 0.25));
+}
+
+#[test]
+fn faces_with_normals() {
+    let file = "v 0 1 0
+v -1 0 0
+v 1 0 0
+vt 0 0
+vn -1 0 0
+vn 1 0 0
+vn 0 1 0
+
+f 1//3 2//1 3//2
+f 1/1/3 2/1/1 3/1/2
+f -3//-1 -2//-3 -1//-2";
+    let parser = parse_obj(file);
+    assert_eq!(parser.ignored.len(), 0);
+    for shape in parser.default_group.iter() {
+        let t = shape.as_any().downcast_ref::<SmoothTriangle>().unwrap();
+        assert_eq!(t.p1, parser.vertices[0]);
+        assert_eq!(t.p2, parser.vertices[1]);
+        assert_eq!(t.p3, parser.vertices[2]);
+        assert_eq!(t.n1, parser.normals[2]);
+        assert_eq!(t.n2, parser.normals[0]);
+        assert_eq!(t.n3, parser.normals[1]);
+    }
+}
+
+#[test]
+fn reading_a_missing_file() {
+    assert!(ObjParser::from_file("./does/not/exist.obj").is_err());
+}
+
+#[test]
+fn converting_obj_file_to_group() {
+    let file = "v -1 1 0
+v -1 0 0
+v 1 0 0
+v 1 1 0
+
+f 1 2 3
+g FirstGroup
+f 1 2 3
+g SecondGroup
+f 1 3 4";
+    let g = parse_obj(file).into_group();
+    assert_eq!(g.children().len(), 3);
+    assert!(g.children()[0].as_any().downcast_ref::<Triangle>().is_some());
+    let first = g.children()[1].as_any().downcast_ref::<Group>().unwrap();
+    assert_eq!(first.children().len(), 1);
+}
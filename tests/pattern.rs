@@ -0,0 +1,283 @@
+use std::any::Any;
+use std::sync::Arc;
+
+use raytracer::color::{BLACK, WHITE, Color};
+use raytracer::light::Light;
+use raytracer::material::Material;
+use raytracer::matrix::Mat4;
+use raytracer::matrix::mat4::{rotate_y, scale, translate};
+use raytracer::pattern::{Blended, Checker, Gradient, Pattern, Perturbed, Ring, Solid, Stripe};
+use raytracer::pattern::perlin::noise;
+use raytracer::shape::{Shape, Sphere};
+use raytracer::tuple::*;
+
+/// A pattern that returns the point it is evaluated
+/// at as a color, to check the transformations
+#[derive(Debug, Clone, PartialEq)]
+struct TestPattern {
+    transform: Mat4,
+}
+
+impl TestPattern {
+    fn new() -> Self {
+        Self { transform: Mat4::identity() }
+    }
+}
+
+impl Pattern for TestPattern {
+    fn local_pattern_at(&self, p: Point) -> Color {
+        Color::new(p.x, p.y, p.z)
+    }
+    fn transform(&self) -> Mat4 {
+        self.transform
+    }
+    fn inverse_transform(&self) -> Mat4 {
+        self.transform.inv()
+    }
+    fn set_transform(&mut self, t: Mat4) {
+        self.transform = t;
+    }
+    fn clone_box(&self) -> Box<dyn Pattern> {
+        Box::new(self.clone())
+    }
+    fn as_any(&self) -> &dyn Any {
+        self
+    }
+    fn eq_box(&self, other: &dyn Any) -> bool {
+        other.downcast_ref::<Self>() == Some(self)
+    }
+}
+
+#[test]
+fn default_pattern_transformation() {
+    let p = TestPattern::new();
+    assert_eq!(p.transform(), Mat4::identity());
+}
+
+#[test]
+fn assigning_pattern_transformation() {
+    let mut p = TestPattern::new();
+    p.set_transform(translate(1., 2., 3.));
+    assert_eq!(p.transform(), translate(1., 2., 3.));
+}
+
+#[test]
+fn pattern_with_object_transformation() {
+    let s = Sphere::new(Some(scale(2., 2., 2.)), None);
+    let p = TestPattern::new();
+    let c = p.pattern_at_shape(&s, Point::new(2., 3., 4.));
+    assert_eq!(c, Color::new(1., 1.5, 2.));
+}
+
+#[test]
+fn pattern_with_pattern_transformation() {
+    let s = Sphere::new(None, None);
+    let mut p = TestPattern::new();
+    p.set_transform(scale(2., 2., 2.));
+    let c = p.pattern_at_shape(&s, Point::new(2., 3., 4.));
+    assert_eq!(c, Color::new(1., 1.5, 2.));
+}
+
+#[test]
+fn pattern_with_object_and_pattern_transformation() {
+    let s = Sphere::new(Some(scale(2., 2., 2.)), None);
+    let mut p = TestPattern::new();
+    p.set_transform(translate(0.5, 1., 1.5));
+    let c = p.pattern_at_shape(&s, Point::new(2.5, 3., 3.5));
+    assert_eq!(c, Color::new(0.75, 0.5, 0.25));
+}
+
+#[test]
+fn stripe_pattern_is_constant_in_y_and_z() {
+    let p = Stripe::new(WHITE, BLACK, None);
+    assert_eq!(p.pattern_at(Point::new(0., 0., 0.)), WHITE);
+    assert_eq!(p.pattern_at(Point::new(0., 1., 0.)), WHITE);
+    assert_eq!(p.pattern_at(Point::new(0., 2., 0.)), WHITE);
+    assert_eq!(p.pattern_at(Point::new(0., 0., 1.)), WHITE);
+    assert_eq!(p.pattern_at(Point::new(0., 0., 2.)), WHITE);
+}
+
+#[test]
+fn stripe_pattern_alternates_in_x() {
+    let p = Stripe::new(WHITE, BLACK, None);
+    assert_eq!(p.pattern_at(Point::new(0., 0., 0.)), WHITE);
+    assert_eq!(p.pattern_at(Point::new(0.9, 0., 0.)), WHITE);
+    assert_eq!(p.pattern_at(Point::new(1., 0., 0.)), BLACK);
+    assert_eq!(p.pattern_at(Point::new(-0.1, 0., 0.)), BLACK);
+    assert_eq!(p.pattern_at(Point::new(-1., 0., 0.)), BLACK);
+    assert_eq!(p.pattern_at(Point::new(-1.1, 0., 0.)), WHITE);
+}
+
+#[test]
+fn stripes_with_object_and_pattern_transformation() {
+    let s = Sphere::new(Some(scale(2., 2., 2.)), None);
+    let p = Stripe::new(WHITE, BLACK, Some(translate(0.5, 0., 0.)));
+    assert_eq!(p.pattern_at_shape(&s, Point::new(2.5, 0., 0.)), WHITE);
+}
+
+#[test]
+fn lighting_with_pattern_applied() {
+    let m = Material {
+        ambient: 1.0,
+        diffuse: 0.0,
+        specular: 0.0,
+        pattern: Some(Arc::new(Stripe::new(WHITE, BLACK, None))),
+        ..Material::default()
+    };
+    let s = Sphere::new(None, None);
+    let eyev = Vector::new(0., 0., -1.);
+    let normalv = Vector::new(0., 0., -1.);
+    let light = Light::new(Point::new(0., 0., -10.), WHITE);
+    let c1 = m.lighting(&s, &light, Point::new(0.9, 0., 0.), eyev, normalv, 1.0);
+    let c2 = m.lighting(&s, &light, Point::new(1.1, 0., 0.), eyev, normalv, 1.0);
+    assert_eq!(c1, WHITE);
+    assert_eq!(c2, BLACK);
+}
+
+#[test]
+fn material_pattern_follows_the_object() {
+    let m = Material {
+        ambient: 1.0,
+        diffuse: 0.0,
+        specular: 0.0,
+        pattern: Some(Arc::new(Stripe::new(WHITE, BLACK, None))),
+        ..Material::default()
+    };
+    let s = Sphere::new(Some(translate(1., 0., 0.)), Some(m));
+    let c = s.material().lighting(&s, &Light::new(Point::new(0., 0., -10.), WHITE),
+        Point::new(1.5, 0., 0.), Vector::new(0., 0., -1.), Vector::new(0., 0., -1.), 1.0);
+    assert_eq!(c, WHITE);
+}
+
+#[test]
+fn gradient_linearly_interpolates_between_colors() {
+    let p = Gradient::new(WHITE, BLACK, None);
+    assert_eq!(p.pattern_at(Point::new(0., 0., 0.)), WHITE);
+    assert_eq!(p.pattern_at(Point::new(0.25, 0., 0.)), Color::new(0.75, 0.75, 0.75));
+    assert_eq!(p.pattern_at(Point::new(0.5, 0., 0.)), Color::new(0.5, 0.5, 0.5));
+    assert_eq!(p.pattern_at(Point::new(0.75, 0., 0.)), Color::new(0.25, 0.25, 0.25));
+}
+
+#[test]
+fn ring_extends_in_both_x_and_z() {
+    let p = Ring::new(WHITE, BLACK, None);
+    assert_eq!(p.pattern_at(Point::new(0., 0., 0.)), WHITE);
+    assert_eq!(p.pattern_at(Point::new(1., 0., 0.)), BLACK);
+    assert_eq!(p.pattern_at(Point::new(0., 0., 1.)), BLACK);
+    // 0.708 = just slightly more than sqrt(2)/2
+    assert_eq!(p.pattern_at(Point::new(0.708, 0., 0.708)), BLACK);
+}
+
+#[test]
+fn checkers_repeat_in_x() {
+    let p = Checker::new(WHITE, BLACK, None);
+    assert_eq!(p.pattern_at(Point::new(0., 0., 0.)), WHITE);
+    assert_eq!(p.pattern_at(Point::new(0.99, 0., 0.)), WHITE);
+    assert_eq!(p.pattern_at(Point::new(1.01, 0., 0.)), BLACK);
+}
+
+#[test]
+fn checkers_repeat_in_y() {
+    let p = Checker::new(WHITE, BLACK, None);
+    assert_eq!(p.pattern_at(Point::new(0., 0., 0.)), WHITE);
+    assert_eq!(p.pattern_at(Point::new(0., 0.99, 0.)), WHITE);
+    assert_eq!(p.pattern_at(Point::new(0., 1.01, 0.)), BLACK);
+}
+
+#[test]
+fn checkers_repeat_in_z() {
+    let p = Checker::new(WHITE, BLACK, None);
+    assert_eq!(p.pattern_at(Point::new(0., 0., 0.)), WHITE);
+    assert_eq!(p.pattern_at(Point::new(0., 0., 0.99)), WHITE);
+    assert_eq!(p.pattern_at(Point::new(0., 0., 1.01)), BLACK);
+}
+
+#[test]
+fn materials_with_different_patterns_differ() {
+    let a = Material { pattern: Some(Arc::new(Stripe::new(WHITE, BLACK, None))), ..Material::default() };
+    let b = Material { pattern: Some(Arc::new(Checker::new(WHITE, BLACK, None))), ..Material::default() };
+    assert_eq!(a, a.clone());
+    assert_ne!(a, b);
+}
+
+#[test]
+fn solid_pattern_is_the_same_everywhere() {
+    let p = Solid::new(Color::new(0.2, 0.4, 0.6));
+    assert_eq!(p.pattern_at(Point::new(0., 0., 0.)), Color::new(0.2, 0.4, 0.6));
+    assert_eq!(p.pattern_at(Point::new(-3.5, 12., 0.7)), Color::new(0.2, 0.4, 0.6));
+}
+
+#[test]
+fn blended_pattern_averages_its_patterns() {
+    let a = Stripe::new(WHITE, BLACK, None);
+    let b = Stripe::new(WHITE, BLACK, Some(rotate_y(std::f64::consts::PI / 2.0)));
+    let p = Blended::new(Box::new(a), Box::new(b), None);
+    // both white, one white, both black
+    assert_eq!(p.pattern_at(Point::new(0.5, 0., -0.5)), WHITE);
+    assert_eq!(p.pattern_at(Point::new(1.5, 0., -0.5)), Color::new(0.5, 0.5, 0.5));
+    assert_eq!(p.pattern_at(Point::new(1.5, 0., 0.5)), BLACK);
+}
+
+#[test]
+fn checker_with_nested_stripes() {
+    let red = Color::new(1., 0., 0.);
+    let a = Stripe::new(WHITE, BLACK, Some(scale(0.25, 0.25, 0.25)));
+    let b = Stripe::new(red, BLACK, Some(scale(0.25, 0.25, 0.25)));
+    let p = Checker::nested(Box::new(a), Box::new(b), None);
+    // first square: white and black stripes a quarter unit wide
+    assert_eq!(p.pattern_at(Point::new(0.1, 0., 0.1)), WHITE);
+    assert_eq!(p.pattern_at(Point::new(0.3, 0., 0.1)), BLACK);
+    // next square: red and black stripes
+    assert_eq!(p.pattern_at(Point::new(1.1, 0., 0.1)), red);
+    assert_eq!(p.pattern_at(Point::new(1.3, 0., 0.1)), BLACK);
+}
+
+#[test]
+fn gradient_between_nested_patterns() {
+    let a = Solid::new(WHITE);
+    let b = Checker::new(BLACK, WHITE, None);
+    let p = Gradient::nested(Box::new(a), Box::new(b), None);
+    assert_eq!(p.pattern_at(Point::new(0.5, 0., 0.)), Color::new(0.5, 0.5, 0.5));
+    assert_eq!(p.pattern_at(Point::new(1.5, 0., 0.)), WHITE);
+}
+
+#[test]
+fn perlin_noise_is_zero_on_the_lattice() {
+    assert_eq!(noise(0., 0., 0.), 0.0);
+    assert_eq!(noise(1., 2., 3.), 0.0);
+    assert_eq!(noise(-4., 7., -1.), 0.0);
+}
+
+#[test]
+fn perlin_noise_is_deterministic_and_bounded() {
+    let mut varied = false;
+    for i in 0..1000 {
+        let x = i as f64 * 0.173 - 50.0;
+        let n = noise(x, x * 0.7, -x * 1.3);
+        assert_eq!(n, noise(x, x * 0.7, -x * 1.3));
+        assert!((-1.0..=1.0).contains(&n));
+        varied |= n.abs() > 0.1;
+    }
+    assert!(varied);
+}
+
+#[test]
+fn perturbed_pattern_without_scale_matches_the_inner_pattern() {
+    let inner = Stripe::new(WHITE, BLACK, None);
+    let p = Perturbed::new(Box::new(inner.clone()), 0.0, None);
+    for i in 0..20 {
+        let point = Point::new(i as f64 * 0.37, 0.5, 0.25);
+        assert_eq!(p.pattern_at(point), inner.pattern_at(point));
+    }
+}
+
+#[test]
+fn perturbed_pattern_moves_the_stripe_edges() {
+    let inner = Stripe::new(WHITE, BLACK, None);
+    let p = Perturbed::new(Box::new(inner.clone()), 0.5, None);
+    let changed = (0..200)
+        .map(|i| Point::new(i as f64 * 0.051, 0.3, 0.7))
+        .filter(|point| p.pattern_at(*point) != inner.pattern_at(*point))
+        .count();
+    assert!(changed > 0);
+}
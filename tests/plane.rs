@@ -1,4 +1,7 @@
+use std::f64::consts::PI;
 
+use raytracer::f64eq;
+use raytracer::matrix::mat4;
 use raytracer::ray::Ray;
 use raytracer::shape::Shape;
 use raytracer::shape::Plane;
@@ -39,3 +42,40 @@ fn ray_intersect_plane_from_below() {
     assert_eq!(xs[0].object.id(), s.id);
 }
 
+#[test]
+fn normal_of_plane_is_constant_everywhere() {
+    let p = Plane::new(None, None);
+    assert_eq!(p.normal_at(Point::new(0.0, 0.0, 0.0)), Vector::new(0.0, 1.0, 0.0));
+    assert_eq!(p.normal_at(Point::new(10.0, 0.0, -10.0)), Vector::new(0.0, 1.0, 0.0));
+    assert_eq!(p.normal_at(Point::new(-5.0, 0.0, 150.0)), Vector::new(0.0, 1.0, 0.0));
+}
+
+#[test]
+fn intersect_translated_plane() {
+    let r = Ray::new(Point::new(0.0, 5.0, 0.0), Vector::new(0.0, -1.0, 0.0));
+    let p = Plane::new(Some(mat4::translate(0.0, 2.0, 0.0)), None);
+    let xs = p.intersect(r);
+    assert_eq!(xs.len(), 1);
+    assert_eq!(xs[0].t, 3.0);
+}
+
+#[test]
+fn intersect_plane_rotated_into_a_wall() {
+    // the xz plane turned into a wall 5 units in front of the origin
+    let p = Plane::new(Some(mat4::translate(0.0, 0.0, 5.0) * mat4::rotate_x(PI / 2.0)), None);
+    let r = Ray::new(Point::new(0.0, 0.0, 0.0), Vector::new(0.0, 0.0, 1.0));
+    let xs = p.intersect(r);
+    assert_eq!(xs.len(), 1);
+    assert!(f64eq(xs[0].t, 5.0));
+    // a ray running along the wall never hits it
+    let r = Ray::new(Point::new(0.0, 0.0, 5.0), Vector::new(1.0, 0.0, 0.0));
+    assert_eq!(p.intersect(r).len(), 0);
+}
+
+#[test]
+fn normal_of_rotated_plane_is_transformed() {
+    let p = Plane::new(Some(mat4::translate(0.0, 0.0, 5.0) * mat4::rotate_x(PI / 2.0)), None);
+    let n = p.normal_at(Point::new(1.0, 2.0, 5.0));
+    assert_eq!(n, Vector::new(0.0, 0.0, 1.0));
+    assert!(f64eq(n.magnitude(), 1.0));
+}
@@ -0,0 +1,95 @@
+use raytracer::f64eq;
+use raytracer::sampler::{grid, halton, jitter, Filter, Sampler};
+
+#[test]
+fn grid_sampler_centers_the_cells() {
+    assert_eq!(Sampler::Grid.offsets(1, 3, 4), vec![(0.5, 0.5)]);
+    assert_eq!(
+        Sampler::Grid.offsets(4, 3, 4),
+        vec![(0.25, 0.25), (0.75, 0.25), (0.25, 0.75), (0.75, 0.75)]
+    );
+    // 8 samples are 2 columns by 4 rows
+    let offsets = Sampler::Grid.offsets(8, 0, 0);
+    assert_eq!(offsets.len(), 8);
+    assert_eq!(offsets[1], (0.75, 0.125));
+    assert_eq!(offsets[7], (0.75, 0.875));
+}
+
+#[test]
+fn jittered_samples_stay_in_their_cell_and_are_repeatable() {
+    let offsets = Sampler::Jittered.offsets(16, 10, 20);
+    assert_eq!(offsets, Sampler::Jittered.offsets(16, 10, 20));
+    assert_ne!(offsets, Sampler::Jittered.offsets(16, 11, 20));
+    for (i, (dx, dy)) in offsets.into_iter().enumerate() {
+        let (col, row) = ((i % 4) as f64, (i / 4) as f64);
+        assert!(dx >= col / 4. && dx < (col + 1.) / 4.);
+        assert!(dy >= row / 4. && dy < (row + 1.) / 4.);
+    }
+}
+
+#[test]
+fn halton_sequence() {
+    assert_eq!(halton(1, 2), 0.5);
+    assert_eq!(halton(2, 2), 0.25);
+    assert_eq!(halton(3, 2), 0.75);
+    assert!(f64eq(halton(1, 3), 1. / 3.));
+    assert!(f64eq(halton(2, 3), 2. / 3.));
+    assert!(f64eq(halton(3, 3), 1. / 9.));
+    let offsets = Sampler::Halton.offsets(3, 0, 0);
+    assert_eq!(offsets.len(), 3);
+    assert_eq!(offsets[0].0, 0.5);
+    assert!(f64eq(offsets[2].1, 1. / 9.));
+}
+
+#[test]
+fn box_filter_weights_all_samples_the_same() {
+    assert_eq!(Filter::Box.weight(0., 0.), 1.0);
+    assert_eq!(Filter::Box.weight(0.5, -0.5), 1.0);
+}
+
+#[test]
+fn tent_filter_falls_linearly() {
+    assert_eq!(Filter::Tent.weight(0., 0.), 1.0);
+    assert_eq!(Filter::Tent.weight(0.5, 0.), 0.5);
+    assert_eq!(Filter::Tent.weight(-0.5, 0.5), 0.25);
+    assert_eq!(Filter::Tent.weight(1.5, 0.), 0.0);
+}
+
+#[test]
+fn gaussian_filter_is_a_bell_curve() {
+    let g = Filter::gaussian(0.5).unwrap();
+    assert_eq!(g, Filter::Gaussian(0.5));
+    assert!(g.weight(0., 0.) > g.weight(0.25, 0.));
+    assert!(g.weight(0.25, 0.) > g.weight(0.5, 0.));
+    assert!(f64eq(g.weight(0.3, 0.1), g.weight(-0.1, -0.3)));
+    assert!(g.weight(0.5, 0.5) > 0.0);
+}
+
+#[test]
+fn gaussian_filter_needs_a_positive_deviation() {
+    assert!(Filter::gaussian(0.0).is_err());
+    assert!(Filter::gaussian(-0.5).is_err());
+    assert!(Filter::gaussian(f64::NAN).is_err());
+    assert!(Filter::gaussian(f64::INFINITY).is_err());
+    // built by hand it still gives finite weights
+    for sigma in [0.0, -0.5, f64::NAN] {
+        assert_eq!(Filter::Gaussian(sigma).weight(0.25, 0.), 1.0);
+    }
+}
+
+#[test]
+fn grid_of_samples_is_as_square_as_possible() {
+    assert_eq!(grid(0), (1, 1));
+    assert_eq!(grid(1), (1, 1));
+    assert_eq!(grid(8), (2, 4));
+    assert_eq!(grid(16), (4, 4));
+    assert_eq!(grid(7), (1, 7));
+}
+
+#[test]
+fn jitter_is_repeatable_and_between_0_and_1() {
+    let (u, v) = jitter(&[1, 2, 3]);
+    assert_eq!(jitter(&[1, 2, 3]), (u, v));
+    assert_ne!(jitter(&[1, 2, 4]), (u, v));
+    assert!((0.0..1.0).contains(&u) && (0.0..1.0).contains(&v));
+}
@@ -0,0 +1,318 @@
+use raytracer::color::{Color, WHITE};
+use raytracer::light::{AreaLight, Attenuation, DirectionalLight, Light, LightSource, SpotLight};
+use raytracer::matrix::mat4::{rotate_x, scale, translate, view_transform};
+use raytracer::scene::Scene;
+use raytracer::shape::{Csg, CsgOperation, Cylinder, Group, Shape, Sphere};
+use raytracer::tuple::{Point, Vector};
+use std::f64::consts::PI;
+
+const CAMERA: &str = "
+- add: camera
+  width: 100
+  height: 50
+  field-of-view: 0.785
+  from: [ -6, 6, -10 ]
+  to: [ 6, 0, 6 ]
+  up: [ -0.45, 1, 0 ]
+";
+
+fn parse(items: &str) -> Scene {
+    Scene::parse(&format!("{}{}", CAMERA, items)).unwrap()
+}
+
+fn parse_error(items: &str) -> String {
+    Scene::parse(&format!("{}{}", CAMERA, items)).unwrap_err()
+}
+
+#[test]
+fn loading_the_camera() {
+    let scene = parse("");
+    assert_eq!(scene.camera.hsize, 100);
+    assert_eq!(scene.camera.vsize, 50);
+    assert_eq!(scene.camera.fov, 0.785);
+    let from = Point::new(-6., 6., -10.);
+    let to = Point::new(6., 0., 6.);
+    let up = Vector::new(-0.45, 1., 0.);
+    assert_eq!(scene.camera.transform(), view_transform(from, to, up));
+    assert!(scene.world.lights.is_empty());
+    assert!(scene.world.objects.is_empty());
+}
+
+#[test]
+fn loading_lights() {
+    let scene = parse("
+- add: light
+  at: [ 50, 100, -50 ]
+  intensity: [ 1, 1, 1 ]
+- add: light
+  corner: [ -1, 2, 4 ]
+  uvec: [ 2, 0, 0 ]
+  usteps: 4
+  vvec: [ 0, 2, 0 ]
+  vsteps: 2
+  jitter: false
+  intensity: [ 0.5, 0.5, 0.5 ]
+  attenuation: [ inverse-square, 3 ]
+- add: spot-light
+  at: [ 0, 10, 0 ]
+  direction: [ 0, -1, 0 ]
+  inner-angle: 0.3
+  outer-angle: 0.5
+  intensity: [ 1, 0.9, 0.8 ]
+- add: directional-light
+  direction: [ 0, -1, 1 ]
+  intensity: [ 0.2, 0.2, 0.2 ]
+");
+    let lights = &scene.world.lights;
+    assert_eq!(lights.len(), 4);
+    let point = Light::new(Point::new(50., 100., -50.), WHITE);
+    assert!(lights[0].eq_box(point.as_any()));
+
+    let mut area = AreaLight::new(
+        Point::new(-1., 2., 4.), Vector::new(2., 0., 0.), 4, Vector::new(0., 2., 0.), 2, Color::new(0.5, 0.5, 0.5));
+    area.jitter = false;
+    area.attenuation = Attenuation::InverseSquare(3.);
+    assert!(lights[1].eq_box(area.as_any()));
+
+    let spot = SpotLight::new(Point::new(0., 10., 0.), Vector::new(0., -1., 0.), 0.3, 0.5, Color::new(1., 0.9, 0.8));
+    assert!(lights[2].eq_box(spot.as_any()));
+    let sun = DirectionalLight::new(Vector::new(0., -1., 1.), Color::new(0.2, 0.2, 0.2));
+    assert!(lights[3].eq_box(sun.as_any()));
+}
+
+#[test]
+fn loading_shapes_with_materials_and_transforms() {
+    let scene = parse("
+- add: plane
+  material:
+    color: [ 1, 0.9, 0.9 ]
+    specular: 0
+    reflective: 0.3
+  transform:
+    - [ rotate-x, 1.5707963267948966 ]
+    - [ translate, 0, 0, 10 ]
+- add: sphere
+  material:
+    transparency: 0.9
+    refractive-index: 1.5
+    pattern:
+      type: stripes
+      colors:
+        - [ 1, 0, 0 ]
+        - [ 0, 0, 1 ]
+      transform:
+        - [ scale, 0.2, 0.2, 0.2 ]
+- add: cylinder
+  min: -1
+  max: 2
+  closed: true
+");
+    let objects = &scene.world.objects;
+    assert_eq!(objects.len(), 3);
+    assert_eq!(objects[0].transform(), translate(0., 0., 10.) * rotate_x(PI / 2.));
+    let m = objects[0].material();
+    assert_eq!(m.color, Color::new(1., 0.9, 0.9));
+    assert_eq!(m.specular, 0.);
+    assert_eq!(m.reflective, 0.3);
+    assert_eq!(m.diffuse, 0.9);
+
+    let m = objects[1].material();
+    assert_eq!(m.transparency, 0.9);
+    assert_eq!(m.refractive_index, 1.5);
+    let pattern = m.pattern.unwrap();
+    assert_eq!(pattern.transform(), scale(0.2, 0.2, 0.2));
+    assert_eq!(pattern.pattern_at(Point::new(0., 0., 0.)), Color::new(1., 0., 0.));
+    assert_eq!(pattern.pattern_at(Point::new(0.3, 0., 0.)), Color::new(0., 0., 1.));
+
+    let c = objects[2].as_any().downcast_ref::<Cylinder>().unwrap();
+    assert_eq!((c.minimum, c.maximum, c.closed), (-1., 2., true));
+}
+
+#[test]
+fn defining_and_extending_materials_and_transforms() {
+    let scene = parse("
+- define: white-material
+  value:
+    color: [ 1, 1, 1 ]
+    diffuse: 0.7
+    reflective: 0.1
+- define: blue-material
+  extend: white-material
+  value:
+    color: [ 0.537, 0.831, 0.914 ]
+- define: standard-transform
+  value:
+    - [ translate, 1, -1, 1 ]
+    - [ scale, 0.5, 0.5, 0.5 ]
+- define: large-object
+  value:
+    - standard-transform
+    - [ scale, 3.5, 3.5, 3.5 ]
+- add: cube
+  material: blue-material
+  transform:
+    - large-object
+    - [ translate, 8.5, 1.5, -0.5 ]
+");
+    let cube = &scene.world.objects[0];
+    let m = cube.material();
+    assert_eq!(m.color, Color::new(0.537, 0.831, 0.914));
+    assert_eq!(m.diffuse, 0.7);
+    assert_eq!(m.reflective, 0.1);
+    let expected = translate(8.5, 1.5, -0.5) * scale(3.5, 3.5, 3.5) * scale(0.5, 0.5, 0.5) * translate(1., -1., 1.);
+    assert_eq!(cube.transform(), expected);
+}
+
+#[test]
+fn loading_groups_csg_and_defined_shapes() {
+    let scene = parse("
+- define: ball
+  value:
+    add: sphere
+    material:
+      color: [ 1, 0, 0 ]
+- add: group
+  transform:
+    - [ translate, 0, 1, 0 ]
+  children:
+    - add: ball
+      transform:
+        - [ scale, 2, 2, 2 ]
+    - add: cube
+- add: csg
+  operation: difference
+  left:
+    add: cube
+  right:
+    add: ball
+    transform:
+      - [ scale, 1.5, 1.5, 1.5 ]
+");
+    let objects = &scene.world.objects;
+    let group = objects[0].as_any().downcast_ref::<Group>().unwrap();
+    assert_eq!(group.transform(), translate(0., 1., 0.));
+    assert_eq!(group.children().len(), 2);
+    let ball = group.children()[0].as_any().downcast_ref::<Sphere>().unwrap();
+    assert_eq!(ball.transform(), scale(2., 2., 2.));
+    assert_eq!(ball.material().color, Color::new(1., 0., 0.));
+    assert_eq!(ball.parent_transform(), translate(0., 1., 0.));
+
+    let csg = objects[1].as_any().downcast_ref::<Csg>().unwrap();
+    assert_eq!(csg.operation, CsgOperation::Difference);
+    assert_eq!(csg.right().transform(), scale(1.5, 1.5, 1.5));
+    assert_eq!(csg.right().material().color, Color::new(1., 0., 0.));
+}
+
+#[test]
+fn errors_name_the_line_and_the_key() {
+    // the camera takes the first 8 lines, the items start after a blank one
+    let e = parse_error("
+- add: sphere
+  material:
+    diffuse: lots
+");
+    assert_eq!(e, "line 12, key `diffuse`: expected a number, found `lots`");
+
+    let e = parse_error("
+- add: light
+  at: [ 0, 0 ]
+  intensity: [ 1, 1, 1 ]
+");
+    assert_eq!(e, "line 11, key `at`: expected a list of 3 numbers");
+
+    let e = parse_error("
+- add: cube
+  colour: [ 1, 0, 0 ]
+");
+    assert_eq!(e, "line 11, key `colour`: unknown key for a cube");
+
+    let e = parse_error("
+- add: cube
+  material: red-material
+");
+    assert_eq!(e, "line 11, key `material`: `red-material` is not defined");
+
+    let e = parse_error("
+- add: teapot
+");
+    assert_eq!(e, "line 10, key `add`: unknown shape `teapot`");
+
+    let e = parse_error("
+- add: plane
+  transform:
+    - [ translate, 1, 2 ]
+");
+    assert_eq!(e, "line 12, key `transform`: unknown transformation `translate` with 2 arguments");
+
+    let e = parse_error("
+- add: light
+  intensity: [ 1, 1, 1 ]
+");
+    assert_eq!(e, "line 10, key `at`: missing from the light");
+
+    let e = Scene::parse("
+- add: camera
+  width: 0
+  height: 50
+  field-of-view: 0.785
+").unwrap_err();
+    assert_eq!(e, "line 3, key `width`: expected a positive integer, found `0`");
+
+    let e = parse_error("
+- add: light
+  at: [ 0, 0, 0 ]
+  intensity: [ 1, 1, 1 ]
+  attenuation: [ linear, 0 ]
+");
+    assert_eq!(e, "line 13, key `attenuation`: the range of a linear attenuation must be a positive number, found 0");
+}
+
+#[test]
+fn definitions_cannot_refer_to_themselves() {
+    let e = parse_error("
+- define: sphere
+  value:
+    add: sphere
+- add: sphere
+");
+    assert_eq!(e, "line 12, key `add`: `sphere` is defined in terms of itself (sphere -> sphere)");
+
+    let e = parse_error("
+- define: ping
+  value:
+    add: pong
+- define: pong
+  value:
+    add: group
+    children:
+      - add: ping
+- add: ping
+");
+    assert_eq!(e, "line 17, key `add`: `ping` is defined in terms of itself (ping -> pong -> ping)");
+
+    let e = parse_error("
+- define: spin
+  value:
+    - [ rotate-y, 1 ]
+    - spin
+- add: cube
+  transform:
+    - spin
+");
+    assert_eq!(e, "line 13, key `transform`: `spin` is defined in terms of itself (spin -> spin)");
+}
+
+#[test]
+fn a_scene_needs_a_camera() {
+    let e = Scene::parse("- add: sphere\n").unwrap_err();
+    assert!(e.contains("add: camera"), "{}", e);
+    assert!(Scene::parse("").is_err());
+    let e = Scene::parse("- add: [ sphere\n").unwrap_err();
+    assert!(e.contains("line"), "{}", e);
+}
+
+#[test]
+fn loading_a_missing_file() {
+    let e = Scene::from_file("no/such/scene.yml").unwrap_err();
+    assert!(e.contains("no/such/scene.yml"), "{}", e);
+}
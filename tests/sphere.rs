@@ -160,6 +160,6 @@ fn sphere_assign_material() {
     let mut s = Sphere::new(None, None);
     let mut m = Material::default();
     m.ambient = 1.0;
-    s.set_material(m);
+    s.set_material(m.clone());
     assert_eq!(s.material(), m);
 }
@@ -0,0 +1,118 @@
+use raytracer::canvas::Canvas;
+use raytracer::color::{linear_to_srgb, srgb_to_linear, Color, Transfer};
+use raytracer::tonemap::{ToneMap, ToneOperator};
+
+#[test]
+fn srgb_transfer_function() {
+    assert_eq!(linear_to_srgb(0.), 0.);
+    assert!((linear_to_srgb(1.) - 1.).abs() < 1e-12);
+    assert!((linear_to_srgb(0.002) - 0.002 * 12.92).abs() < 1e-12);
+    assert!((linear_to_srgb(0.5) - 0.735357).abs() < 1e-6);
+    for i in 0..=100 {
+        let c = i as f64 / 100.;
+        assert!((srgb_to_linear(linear_to_srgb(c)) - c).abs() < 1e-12);
+    }
+}
+
+#[test]
+fn srgb_encoding_is_a_write_option() {
+    let c = Color::new(0.5, 0.2140, 0.);
+    assert_eq!(c.to_bytes(), (128, 54, 0));
+    assert_eq!(c.encode(Transfer::Linear), c);
+    assert_eq!(c.encode(Transfer::Srgb).to_bytes(), (188, 127, 0));
+    assert_eq!(Color::new(2., -1., 0.).encode(Transfer::Srgb), Color::new(1., 0., 0.));
+    for v in 0..=255 {
+        let c = Color::from((v, v, v)).decode(Transfer::Srgb);
+        assert_eq!(c.encode(Transfer::Srgb).to_bytes(), (v, v, v));
+    }
+}
+
+#[test]
+fn png_files_are_srgb_and_ppm_files_linear() {
+    let mut c = Canvas::new(1, 1);
+    c[(0, 0)] = Color::new(0.5, 0.5, 0.5);
+    let dir = std::env::temp_dir();
+    let png = dir.join(format!("raytracer-srgb-{}.png", std::process::id()));
+    let ppm = dir.join(format!("raytracer-srgb-{}.ppm", std::process::id()));
+    let (png, ppm) = (png.to_str().unwrap(), ppm.to_str().unwrap());
+
+    c.save(png).unwrap();
+    let img = image::open(png).unwrap().to_rgb8();
+    assert_eq!(img.get_pixel(0, 0)[0], 188);
+    // loading decodes the sRGB back
+    assert!((Canvas::load(png).unwrap()[(0, 0)].r - 0.5).abs() < 0.005);
+
+    c.save(ppm).unwrap();
+    assert_eq!(std::fs::read_to_string(ppm).unwrap(), "P3\n1 1\n255\n128 128 128\n");
+    c.save_with(ppm, Some(Transfer::Srgb)).unwrap();
+    assert_eq!(std::fs::read_to_string(ppm).unwrap(), "P3\n1 1\n255\n188 188 188\n");
+    c.save_with(png, Some(Transfer::Linear)).unwrap();
+    assert_eq!(image::open(png).unwrap().to_rgb8().get_pixel(0, 0)[0], 128);
+
+    std::fs::remove_file(png).unwrap();
+    std::fs::remove_file(ppm).unwrap();
+}
+
+#[test]
+fn tone_operators() {
+    let ops = [ToneOperator::Clamp, ToneOperator::Reinhard, ToneOperator::ExtendedReinhard(4.), ToneOperator::Aces];
+    for op in ops.iter() {
+        assert_eq!(op.map(0.), 0.);
+        assert_eq!(op.map(-1.), 0.);
+        assert!(op.map(100.) <= 1.);
+        // brighter stays brighter
+        assert!((1..100).all(|i| op.map(i as f64 * 0.05) >= op.map((i - 1) as f64 * 0.05)));
+    }
+    assert_eq!(ToneOperator::Clamp.map(0.3), 0.3);
+    assert_eq!(ToneOperator::Clamp.map(3.), 1.);
+    assert_eq!(ToneOperator::Reinhard.map(1.), 0.5);
+    assert_eq!(ToneOperator::Reinhard.map(3.), 0.75);
+    assert_eq!(ToneOperator::ExtendedReinhard(4.).map(4.), 1.);
+    assert_eq!(ToneOperator::ExtendedReinhard(4.).map(8.), 1.);
+    assert!((ToneOperator::Aces.map(1.) - 0.803797).abs() < 1e-6);
+}
+
+#[test]
+fn parsing_tone_operators() {
+    assert_eq!(ToneOperator::parse("clamp"), Ok(ToneOperator::Clamp));
+    assert_eq!(ToneOperator::parse("reinhard"), Ok(ToneOperator::Reinhard));
+    assert_eq!(ToneOperator::parse("aces"), Ok(ToneOperator::Aces));
+    assert_eq!(ToneOperator::parse("extended-reinhard:2.5"), Ok(ToneOperator::ExtendedReinhard(2.5)));
+    assert!(ToneOperator::parse("extended-reinhard").is_err());
+    assert!(ToneOperator::parse("extended-reinhard:0").is_err());
+    assert!(ToneOperator::parse("filmic").is_err());
+}
+
+#[test]
+fn exposure_is_in_stops() {
+    let c = Color::new(0.1, 0.2, 0.4);
+    assert_eq!(ToneMap::default().apply(c), c);
+    assert_eq!(ToneMap::new(1., ToneOperator::Clamp).apply(c), Color::new(0.2, 0.4, 0.8));
+    assert_eq!(ToneMap::new(-2., ToneOperator::Clamp).apply(c), Color::new(0.025, 0.05, 0.1));
+    assert_eq!(ToneMap::new(3., ToneOperator::Reinhard).apply(c), Color::new(0.8 / 1.8, 1.6 / 2.6, 3.2 / 4.2));
+}
+
+#[test]
+fn exposing_keeps_the_colors_above_one() {
+    let tone_map = ToneMap::new(2., ToneOperator::Aces);
+    assert_eq!(tone_map.expose(Color::new(0.5, 1., -1.)), Color::new(2., 4., -4.));
+    let mut c = Canvas::new(1, 1);
+    c[(0, 0)] = Color::new(0.5, 1., 2.);
+    assert_eq!(c.expose(&tone_map)[(0, 0)], Color::new(2., 4., 8.));
+    assert!(Canvas::is_hdr_file("render/out.EXR"));
+    assert!(Canvas::is_hdr_file("out.hdr") && Canvas::is_hdr_file("out.pfm"));
+    assert!(!Canvas::is_hdr_file("out.png") && !Canvas::is_hdr_file("hdr"));
+}
+
+#[test]
+fn tone_mapping_a_canvas() {
+    let mut c = Canvas::new(2, 1);
+    c[(0, 0)] = Color::new(1., 3., 0.);
+    c[(1, 0)] = Color::new(0.5, 0.5, 0.5);
+    let mapped = c.tone_map(&ToneMap::new(0., ToneOperator::Reinhard));
+    assert_eq!((mapped.width, mapped.height), (2, 1));
+    assert_eq!(mapped[(0, 0)], Color::new(0.5, 0.75, 0.));
+    assert_eq!(mapped[(1, 0)], Color::new(0.5 / 1.5, 0.5 / 1.5, 0.5 / 1.5));
+    // the original keeps its colors
+    assert_eq!(c[(0, 0)], Color::new(1., 3., 0.));
+}
@@ -0,0 +1,118 @@
+use raytracer::intersection::{Intersection, Intersections};
+use raytracer::ray::Ray;
+use raytracer::shape::Shape;
+use raytracer::shape::{SmoothTriangle, Triangle};
+use raytracer::tuple::{Point, Vector};
+
+fn default_triangle() -> Triangle {
+    Triangle::new(
+        Point::new(0.0, 1.0, 0.0),
+        Point::new(-1.0, 0.0, 0.0),
+        Point::new(1.0, 0.0, 0.0),
+    )
+}
+
+fn default_smooth_triangle() -> SmoothTriangle {
+    SmoothTriangle::new(
+        Point::new(0.0, 1.0, 0.0),
+        Point::new(-1.0, 0.0, 0.0),
+        Point::new(1.0, 0.0, 0.0),
+        Vector::new(0.0, 1.0, 0.0),
+        Vector::new(-1.0, 0.0, 0.0),
+        Vector::new(1.0, 0.0, 0.0),
+    )
+}
+
+#[test]
+fn constructing_triangle() {
+    let t = default_triangle();
+    assert_eq!(t.p1, Point::new(0.0, 1.0, 0.0));
+    assert_eq!(t.p2, Point::new(-1.0, 0.0, 0.0));
+    assert_eq!(t.p3, Point::new(1.0, 0.0, 0.0));
+    assert_eq!(t.e1, Vector::new(-1.0, -1.0, 0.0));
+    assert_eq!(t.e2, Vector::new(1.0, -1.0, 0.0));
+    assert_eq!(t.normal, Vector::new(0.0, 0.0, -1.0));
+}
+
+#[test]
+fn normal_on_triangle() {
+    let t = default_triangle();
+    assert_eq!(t.normal_at(Point::new(0.0, 0.5, 0.0)), t.normal);
+    assert_eq!(t.normal_at(Point::new(-0.5, 0.75, 0.0)), t.normal);
+    assert_eq!(t.normal_at(Point::new(0.5, 0.25, 0.0)), t.normal);
+}
+
+#[test]
+fn ray_parallel_to_triangle() {
+    let t = default_triangle();
+    let r = Ray::new(Point::new(0.0, -1.0, -2.0), Vector::new(0.0, 1.0, 0.0));
+    assert_eq!(t.intersect(r).len(), 0);
+}
+
+#[test]
+fn ray_misses_triangle_edges() {
+    let t = default_triangle();
+    let origins = vec![
+        Point::new(1.0, 1.0, -2.0),
+        Point::new(-1.0, 1.0, -2.0),
+        Point::new(0.0, -1.0, -2.0),
+    ];
+    for origin in origins {
+        let r = Ray::new(origin, Vector::new(0.0, 0.0, 1.0));
+        assert_eq!(t.intersect(r).len(), 0);
+    }
+}
+
+#[test]
+fn ray_strikes_triangle() {
+    let t = default_triangle();
+    let r = Ray::new(Point::new(0.0, 0.5, -2.0), Vector::new(0.0, 0.0, 1.0));
+    let xs = t.intersect(r);
+    assert_eq!(xs.len(), 1);
+    assert_eq!(xs[0].t, 2.0);
+    assert_eq!(xs[0].object.id(), t.id);
+}
+
+#[test]
+fn constructing_smooth_triangle() {
+    let t = default_smooth_triangle();
+    assert_eq!(t.p1, Point::new(0.0, 1.0, 0.0));
+    assert_eq!(t.n1, Vector::new(0.0, 1.0, 0.0));
+    assert_eq!(t.n2, Vector::new(-1.0, 0.0, 0.0));
+    assert_eq!(t.n3, Vector::new(1.0, 0.0, 0.0));
+}
+
+#[test]
+fn intersection_can_encapsulate_u_and_v() {
+    let t = default_triangle();
+    let i = Intersection::new_with_uv(3.5, t.clone_box(), 0.2, 0.4);
+    assert_eq!(i.u, 0.2);
+    assert_eq!(i.v, 0.4);
+}
+
+#[test]
+fn intersection_with_smooth_triangle_stores_u_v() {
+    let t = default_smooth_triangle();
+    let r = Ray::new(Point::new(-0.2, 0.3, -2.0), Vector::new(0.0, 0.0, 1.0));
+    let xs = t.intersect(r);
+    assert!(raytracer::f64eq(xs[0].u, 0.45));
+    assert!(raytracer::f64eq(xs[0].v, 0.25));
+}
+
+#[test]
+fn smooth_triangle_uses_u_v_to_interpolate_normal() {
+    let t = default_smooth_triangle();
+    let i = Intersection::new_with_uv(1.0, t.clone_box(), 0.45, 0.25);
+    let n = t.normal_at_hit(Point::new(0.0, 0.0, 0.0), &i);
+    assert_eq!(n, Vector::new(-0.5547, 0.83205, 0.0));
+}
+
+#[test]
+fn preparing_normal_on_smooth_triangle() {
+    let t = default_smooth_triangle();
+    let i = Intersection::new_with_uv(1.0, t.clone_box(), 0.45, 0.25);
+    let r = Ray::new(Point::new(-0.2, 0.3, -2.0), Vector::new(0.0, 0.0, 1.0));
+    let xs = Intersections::new(vec![i]);
+    let comps = xs[0].prepare_computation(r);
+    assert_eq!(comps.normalv, Vector::new(-0.5547, 0.83205, 0.0));
+}
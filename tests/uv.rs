@@ -0,0 +1,235 @@
+use raytracer::canvas::Canvas;
+use raytracer::color::{BLACK, BLUE, CYAN, GREEN, RED, WHITE, YELLOW, Color};
+use raytracer::pattern::uv::{cube_map, cylindrical_map, planar_map, spherical_map, CubeFace};
+use raytracer::pattern::{CubeMap, Pattern, TextureFilter, TextureMap, UvAlignCheck, UvCheckers, UvImage, UvMapping, UvPattern};
+use raytracer::matrix::mat4::scale;
+use raytracer::shape::Sphere;
+use raytracer::tuple::*;
+use std::f64::consts::FRAC_1_SQRT_2;
+use std::sync::Arc;
+
+fn assert_uv((u, v): (f64, f64), expected: (f64, f64)) {
+    assert!((u - expected.0).abs() < 0.0001, "u: {} != {}", u, expected.0);
+    assert!((v - expected.1).abs() < 0.0001, "v: {} != {}", v, expected.1);
+}
+
+#[test]
+fn checker_pattern_in_2d() {
+    let checkers = UvCheckers::new(2, 2, BLACK, WHITE);
+    assert_eq!(checkers.uv_pattern_at(0.0, 0.0), BLACK);
+    assert_eq!(checkers.uv_pattern_at(0.5, 0.0), WHITE);
+    assert_eq!(checkers.uv_pattern_at(0.0, 0.5), WHITE);
+    assert_eq!(checkers.uv_pattern_at(0.5, 0.5), BLACK);
+    assert_eq!(checkers.uv_pattern_at(1.0, 1.0), BLACK);
+}
+
+#[test]
+fn spherical_mapping_on_3d_point() {
+    assert_uv(spherical_map(Point::new(0., 0., -1.)), (0.0, 0.5));
+    assert_uv(spherical_map(Point::new(1., 0., 0.)), (0.25, 0.5));
+    assert_uv(spherical_map(Point::new(0., 0., 1.)), (0.5, 0.5));
+    assert_uv(spherical_map(Point::new(-1., 0., 0.)), (0.75, 0.5));
+    assert_uv(spherical_map(Point::new(0., 1., 0.)), (0.5, 1.0));
+    assert_uv(spherical_map(Point::new(0., -1., 0.)), (0.5, 0.0));
+    assert_uv(spherical_map(Point::new(2f64.sqrt() / 2., 2f64.sqrt() / 2., 0.)), (0.25, 0.75));
+}
+
+#[test]
+fn texture_map_with_spherical_map() {
+    let checkers = UvCheckers::new(16, 8, BLACK, WHITE);
+    let pattern = TextureMap::new(Box::new(checkers), UvMapping::Spherical, None);
+    let cases = [
+        (Point::new(0.4315, 0.4670, 0.7719), WHITE),
+        (Point::new(-0.9654, 0.2552, -0.0534), BLACK),
+        (Point::new(0.1039, 0.7090, 0.6975), WHITE),
+        (Point::new(-0.4986, -0.7856, -0.3663), BLACK),
+        (Point::new(-0.0317, -0.9395, 0.3411), BLACK),
+        (Point::new(0.4809, -0.7721, 0.4154), BLACK),
+        (Point::new(0.0285, -0.9612, -0.2745), BLACK),
+        (Point::new(-0.5734, -0.2162, -0.7903), WHITE),
+        (Point::new(0.7688, -0.1470, 0.6223), BLACK),
+        (Point::new(-0.7652, 0.2175, 0.6060), BLACK),
+    ];
+    for (point, color) in cases {
+        assert_eq!(pattern.pattern_at(point), color);
+    }
+}
+
+#[test]
+fn planar_mapping_on_3d_point() {
+    assert_uv(planar_map(Point::new(0.25, 0., 0.5)), (0.25, 0.5));
+    assert_uv(planar_map(Point::new(0.25, 0., -0.25)), (0.25, 0.75));
+    assert_uv(planar_map(Point::new(0.25, 0.5, -0.25)), (0.25, 0.75));
+    assert_uv(planar_map(Point::new(1.25, 0., 0.5)), (0.25, 0.5));
+    assert_uv(planar_map(Point::new(0.25, 0., -1.75)), (0.25, 0.25));
+    assert_uv(planar_map(Point::new(1., 0., -1.)), (0.0, 0.0));
+    assert_uv(planar_map(Point::new(0., 0., 0.)), (0.0, 0.0));
+}
+
+#[test]
+fn cylindrical_mapping_on_3d_point() {
+    assert_uv(cylindrical_map(Point::new(0., 0., -1.)), (0.0, 0.0));
+    assert_uv(cylindrical_map(Point::new(0., 0.5, -1.)), (0.0, 0.5));
+    assert_uv(cylindrical_map(Point::new(0., 1., -1.)), (0.0, 0.0));
+    assert_uv(cylindrical_map(Point::new(FRAC_1_SQRT_2, 0.5, -FRAC_1_SQRT_2)), (0.125, 0.5));
+    assert_uv(cylindrical_map(Point::new(1., 0.5, 0.)), (0.25, 0.5));
+    assert_uv(cylindrical_map(Point::new(FRAC_1_SQRT_2, 0.5, FRAC_1_SQRT_2)), (0.375, 0.5));
+    assert_uv(cylindrical_map(Point::new(0., -0.25, 1.)), (0.5, 0.75));
+    assert_uv(cylindrical_map(Point::new(-FRAC_1_SQRT_2, 0.5, FRAC_1_SQRT_2)), (0.625, 0.5));
+    assert_uv(cylindrical_map(Point::new(-1., 1.25, 0.)), (0.75, 0.25));
+    assert_uv(cylindrical_map(Point::new(-FRAC_1_SQRT_2, 0.5, -FRAC_1_SQRT_2)), (0.875, 0.5));
+}
+
+#[test]
+fn layout_of_align_check_pattern() {
+    let p = UvAlignCheck::new(WHITE, RED, YELLOW, GREEN, CYAN);
+    assert_eq!(p.uv_pattern_at(0.5, 0.5), WHITE);
+    assert_eq!(p.uv_pattern_at(0.1, 0.9), RED);
+    assert_eq!(p.uv_pattern_at(0.9, 0.9), YELLOW);
+    assert_eq!(p.uv_pattern_at(0.1, 0.1), GREEN);
+    assert_eq!(p.uv_pattern_at(0.9, 0.1), CYAN);
+}
+
+#[test]
+fn identifying_face_of_cube_from_point() {
+    assert_eq!(CubeFace::of(Point::new(-1., 0.5, -0.25)), CubeFace::Left);
+    assert_eq!(CubeFace::of(Point::new(1.1, -0.75, 0.8)), CubeFace::Right);
+    assert_eq!(CubeFace::of(Point::new(0.1, 0.6, 0.9)), CubeFace::Front);
+    assert_eq!(CubeFace::of(Point::new(-0.7, 0., -2.)), CubeFace::Back);
+    assert_eq!(CubeFace::of(Point::new(0.5, 1., 0.9)), CubeFace::Up);
+    assert_eq!(CubeFace::of(Point::new(-0.2, -1.3, 1.1)), CubeFace::Down);
+}
+
+#[test]
+fn uv_mapping_on_every_cube_face() {
+    let cases = [
+        (Point::new(-0.5, 0.5, 1.), Point::new(0.5, -0.5, 1.)),
+        (Point::new(0.5, 0.5, -1.), Point::new(-0.5, -0.5, -1.)),
+        (Point::new(-1., 0.5, -0.5), Point::new(-1., -0.5, 0.5)),
+        (Point::new(1., 0.5, 0.5), Point::new(1., -0.5, -0.5)),
+        (Point::new(-0.5, 1., -0.5), Point::new(0.5, 1., 0.5)),
+        (Point::new(-0.5, -1., 0.5), Point::new(0.5, -1., -0.5)),
+    ];
+    // on every face the first point is up and left, the second down and right
+    for (upper_left, lower_right) in cases {
+        assert_uv(cube_map(upper_left).1, (0.25, 0.75));
+        assert_uv(cube_map(lower_right).1, (0.75, 0.25));
+        assert_uv(UvMapping::Cube.map(upper_left), (0.25, 0.75));
+    }
+}
+
+#[test]
+fn finding_colors_on_mapped_cube() {
+    let purple = Color::new(1., 0., 1.);
+    let brown = Color::new(1., 0.5, 0.);
+    let left = UvAlignCheck::new(YELLOW, CYAN, RED, BLUE, brown);
+    let front = UvAlignCheck::new(CYAN, RED, YELLOW, brown, GREEN);
+    let right = UvAlignCheck::new(RED, YELLOW, purple, GREEN, WHITE);
+    let back = UvAlignCheck::new(GREEN, purple, CYAN, WHITE, BLUE);
+    let up = UvAlignCheck::new(brown, CYAN, purple, RED, YELLOW);
+    let down = UvAlignCheck::new(purple, brown, GREEN, BLUE, WHITE);
+    let pattern = CubeMap::new(Box::new(left), Box::new(front), Box::new(right),
+        Box::new(back), Box::new(up), Box::new(down), None);
+    let cases = [
+        // left
+        (Point::new(-1., 0., 0.), YELLOW),
+        (Point::new(-1., 0.9, -0.9), CYAN),
+        (Point::new(-1., 0.9, 0.9), RED),
+        (Point::new(-1., -0.9, -0.9), BLUE),
+        (Point::new(-1., -0.9, 0.9), brown),
+        // front
+        (Point::new(0., 0., 1.), CYAN),
+        (Point::new(-0.9, 0.9, 1.), RED),
+        (Point::new(0.9, -0.9, 1.), GREEN),
+        // right
+        (Point::new(1., 0., 0.), RED),
+        (Point::new(1., 0.9, 0.9), YELLOW),
+        // back
+        (Point::new(0., 0., -1.), GREEN),
+        (Point::new(0.9, 0.9, -1.), purple),
+        // up
+        (Point::new(0., 1., 0.), brown),
+        (Point::new(-0.9, 1., -0.9), CYAN),
+        // down
+        (Point::new(0., -1., 0.), purple),
+        (Point::new(-0.9, -1., 0.9), brown),
+    ];
+    for (point, color) in cases {
+        assert_eq!(pattern.pattern_at(point), color);
+    }
+}
+
+/// A 2x2 image: red and green on the top row, blue and white on the bottom
+fn small_image() -> Canvas {
+    let mut c = Canvas::new(2, 2);
+    c[(0, 0)] = RED;
+    c[(1, 0)] = GREEN;
+    c[(0, 1)] = BLUE;
+    c[(1, 1)] = WHITE;
+    c
+}
+
+#[test]
+fn image_texture_with_nearest_filter() {
+    let img = UvImage::new(small_image(), TextureFilter::Nearest).unwrap();
+    // v goes up, while the rows of the image go down
+    assert_eq!(img.uv_pattern_at(0.0, 1.0), RED);
+    assert_eq!(img.uv_pattern_at(1.0, 1.0), GREEN);
+    assert_eq!(img.uv_pattern_at(0.0, 0.0), BLUE);
+    assert_eq!(img.uv_pattern_at(0.9, 0.2), WHITE);
+}
+
+#[test]
+fn image_texture_with_bilinear_filter() {
+    let img = UvImage::new(small_image(), TextureFilter::Bilinear).unwrap();
+    assert_eq!(img.uv_pattern_at(0.0, 1.0), RED);
+    assert_eq!(img.uv_pattern_at(1.0, 0.0), WHITE);
+    assert_eq!(img.uv_pattern_at(0.25, 1.0), Color::new(0.75, 0.25, 0.0));
+    assert_eq!(img.uv_pattern_at(0.5, 0.5), Color::new(0.5, 0.5, 0.5));
+}
+
+#[test]
+fn image_texture_on_a_sphere() {
+    let img = UvImage::new(small_image(), TextureFilter::Nearest).unwrap();
+    let pattern = TextureMap::new(Box::new(img), UvMapping::Spherical, None);
+    let s = Sphere::new(None, None);
+    // the north pole is on the top row of the image, the south pole on the bottom
+    assert_eq!(pattern.pattern_at_shape(&s, Point::new(0., 0.999, -0.04)), RED);
+    assert_eq!(pattern.pattern_at_shape(&s, Point::new(0., -0.999, 0.04)), WHITE);
+}
+
+#[test]
+fn loading_image_texture_from_file() {
+    let path = std::env::temp_dir().join("raytracer_uv_texture.png");
+    let path = path.to_str().unwrap();
+    small_image().save(path).unwrap();
+    let img = UvImage::from_file(path, TextureFilter::Nearest).unwrap();
+    assert_eq!(*img.canvas, small_image());
+    assert!(UvImage::from_file("./no/such/texture.png", TextureFilter::Nearest).is_err());
+}
+
+#[test]
+fn image_texture_needs_pixels() {
+    assert!(UvImage::new(Canvas::new(0, 0), TextureFilter::Nearest).is_err());
+    assert!(UvImage::new(Canvas::new(3, 0), TextureFilter::Bilinear).is_err());
+    assert!(UvImage::new(Canvas::new(1, 1), TextureFilter::Bilinear).is_ok());
+}
+
+#[test]
+fn transforming_a_cube_map() {
+    let faces = || -> Box<dyn UvPattern> { Box::new(UvCheckers::new(2, 2, WHITE, BLACK)) };
+    let t = scale(2., 2., 2.);
+    let pattern = CubeMap::new(faces(), faces(), faces(), faces(), faces(), faces(), Some(t));
+    assert_eq!(pattern.transform(), t);
+    assert_eq!(pattern.pattern_at(Point::new(0., 0., 2.)), pattern.local_pattern_at(Point::new(0., 0., 1.)));
+}
+
+#[test]
+fn cloning_an_image_texture_shares_the_image() {
+    let img = UvImage::new(small_image(), TextureFilter::Nearest).unwrap();
+    let pattern = TextureMap::new(Box::new(img.clone()), UvMapping::Spherical, None);
+    let copy = pattern.clone_box();
+    let copy = copy.as_any().downcast_ref::<TextureMap>().unwrap();
+    let copy = copy.uv_pattern.as_any().downcast_ref::<UvImage>().unwrap();
+    assert!(Arc::ptr_eq(&copy.canvas, &img.canvas));
+}
@@ -1,17 +1,17 @@
 use raytracer::color::{BLACK, Color, WHITE};
-use raytracer::intersection::Intersection;
-use raytracer::light::Light;
+use raytracer::intersection::{Intersection, Intersections};
+use raytracer::light::{Light, LightSource};
 use raytracer::material::Material;
-use raytracer::matrix::mat4::{scale, translate};
+use raytracer::matrix::mat4::{rotate_x, scale, translate};
 use raytracer::ray::Ray;
-use raytracer::shape::{Shape, Sphere};
+use raytracer::shape::{Plane, Shape, Sphere};
 use raytracer::world::World;
 use raytracer::tuple::*;
 
 #[test]
 fn creating_world() {
     let w = World::new(None, None);
-    assert!(w.light.is_none());
+    assert!(w.lights.is_empty());
     assert_eq!(w.objects, vec![])
 }
 
@@ -19,7 +19,7 @@ fn creating_world() {
 fn default_world() {
     let w = World::default();
     let ligth = Light::new(Point::new(-10., 10., -10.), WHITE);
-    assert_eq!(w.light, Some(ligth));
+    assert_eq!(w.lights, vec![Box::new(ligth) as Box<dyn LightSource>]);
     assert_eq!(w.objects[0].material().color, Color::new(0.8, 1.0, 0.6));
     assert_eq!(w.objects[0].material().diffuse, 0.7);
     assert_eq!(w.objects[0].material().specular, 0.2);
@@ -50,10 +50,10 @@ fn shading_an_intersection() {
 #[test]
 fn shading_an_intersection_from_inside() {
     let mut w = World::default();
-    w.light = Some(Light::new(
+    w.lights = vec![Box::new(Light::new(
         Point::new(0.0, 0.25, 0.0),
         Color::new(1.0, 1.0, 1.0),
-    ));
+    ))];
     let r = Ray::new(Point::new(0., 0., 0.), Vector::new(0., 0., 1.));
     let i = Intersection::new(0.5, w.objects[1].clone_box());
     let c = w.shade_hit(i.prepare_computation(r));
@@ -91,21 +91,21 @@ fn color_with_intersection_behind_ray() {
 fn shadow_object_between_point_and_light() {
     let w = World::default();
     let p = Point::new(10., -10., 10.);
-    assert!(w.is_shadowed(p))
+    assert!(w.is_shadowed(Point::new(-10., 10., -10.), p))
 }
 
 #[test]
 fn shadow_object_behind_light() {
     let w = World::default();
     let p = Point::new(-20., 20., -20.);
-    assert!(!w.is_shadowed(p))
+    assert!(!w.is_shadowed(Point::new(-10., 10., -10.), p))
 }
 
 #[test]
 fn shadow_object_behind_point() {
     let w = World::default();
     let p = Point::new(-2., 2., -2.);
-    assert!(!w.is_shadowed(p))
+    assert!(!w.is_shadowed(Point::new(-10., 10., -10.), p))
 }
 
 #[test]
@@ -114,10 +114,221 @@ fn shade_it() {
     let t = translate(0., 0., 10.);
     let s1 = Sphere::new(None, None);
     let s2 = Sphere::new(Some(t), None);
-    let w = World::new(Some(light), Some(vec![s1.clone_box(), s2.clone_box()]));
+    let w = World::new(Some(vec![Box::new(light)]), Some(vec![s1.clone_box(), s2.clone_box()]));
     let r = Ray::new(Point::new(0., 0., 5.), Vector::new(0., 0., 1.));
     let i = Intersection::new(4.0, s2.clone_box());
     let comps = i.prepare_computation(r);
     let c = w.shade_hit(comps);
     assert_eq!(c, Color::new(0.1, 0.1, 0.1));
-}
\ No newline at end of file
+}
+
+/// The default world with a reflective plane
+/// below the spheres, half of a mirror
+fn world_with_reflective_floor() -> (World, Plane) {
+    let mut w = World::default();
+    let m = Material { reflective: 0.5, ..Material::default() };
+    let p = Plane::new(Some(translate(0., -1., 0.)), Some(m));
+    w.objects.push(p.clone_box());
+    w.build_bvh();
+    (w, p)
+}
+
+#[test]
+fn reflected_color_for_nonreflective_material() {
+    let w = World::default();
+    let r = Ray::new(Point::new(0., 0., 0.), Vector::new(0., 0., 1.));
+    let mut shape = w.objects[1].clone_box();
+    shape.set_material(Material::new(None, Some(1.0), None, None, None));
+    let i = Intersection::new(1.0, shape);
+    let comps = i.prepare_computation(r);
+    assert_eq!(w.reflected_color(&comps, w.max_depth), BLACK);
+}
+
+#[test]
+fn reflected_color_for_reflective_material() {
+    let (w, p) = world_with_reflective_floor();
+    let r = Ray::new(Point::new(0., 0., -3.), Vector::new(0., -(2f64.sqrt()) / 2., 2f64.sqrt() / 2.));
+    let i = Intersection::new(2f64.sqrt(), p.clone_box());
+    let comps = i.prepare_computation(r);
+    assert_eq!(w.reflected_color(&comps, w.max_depth), Color::new(0.19033, 0.23791, 0.14274));
+}
+
+#[test]
+fn shade_hit_with_reflective_material() {
+    let (w, p) = world_with_reflective_floor();
+    let r = Ray::new(Point::new(0., 0., -3.), Vector::new(0., -(2f64.sqrt()) / 2., 2f64.sqrt() / 2.));
+    let i = Intersection::new(2f64.sqrt(), p.clone_box());
+    let comps = i.prepare_computation(r);
+    assert_eq!(w.shade_hit(comps), Color::new(0.87676, 0.92434, 0.82917));
+}
+
+#[test]
+fn color_at_with_mutually_reflective_surfaces() {
+    let m = Material { reflective: 1.0, ..Material::default() };
+    let lower = Plane::new(Some(translate(0., -1., 0.)), Some(m.clone()));
+    let upper = Plane::new(Some(translate(0., 1., 0.) * rotate_x(std::f64::consts::PI)), Some(m));
+    let light = Light::new(Point::new(0., 0., 0.), WHITE);
+    let w = World::new(Some(vec![Box::new(light)]), Some(vec![lower.clone_box(), upper.clone_box()]));
+    let r = Ray::new(Point::new(0., 0., 0.), Vector::new(0., 1., 0.));
+    // the ray bounces between the two mirrors until the depth runs out
+    let c = w.color_at(r);
+    assert!(c.r > 0.0 && c.r.is_finite());
+}
+
+#[test]
+fn reflected_color_at_maximum_recursive_depth() {
+    let (w, p) = world_with_reflective_floor();
+    let r = Ray::new(Point::new(0., 0., -3.), Vector::new(0., -(2f64.sqrt()) / 2., 2f64.sqrt() / 2.));
+    let i = Intersection::new(2f64.sqrt(), p.clone_box());
+    let comps = i.prepare_computation(r);
+    assert_eq!(w.reflected_color(&comps, 0), BLACK);
+}
+
+#[test]
+fn refracted_color_with_opaque_surface() {
+    let w = World::default();
+    let r = Ray::new(Point::new(0., 0., -5.), Vector::new(0., 0., 1.));
+    let xs = Intersections::new(vec![
+        Intersection::new(4.0, w.objects[0].clone_box()),
+        Intersection::new(6.0, w.objects[0].clone_box()),
+    ]);
+    let comps = xs[0].prepare_computation_with(r, &xs);
+    assert_eq!(w.refracted_color(&comps, 5), BLACK);
+}
+
+#[test]
+fn refracted_color_at_maximum_recursive_depth() {
+    let w = World::default();
+    let mut shape = w.objects[0].clone_box();
+    shape.set_material(Material { transparency: 1.0, refractive_index: 1.5, ..shape.material() });
+    let r = Ray::new(Point::new(0., 0., -5.), Vector::new(0., 0., 1.));
+    let xs = Intersections::new(vec![
+        Intersection::new(4.0, shape.clone()),
+        Intersection::new(6.0, shape.clone()),
+    ]);
+    let comps = xs[0].prepare_computation_with(r, &xs);
+    assert_eq!(w.refracted_color(&comps, 0), BLACK);
+}
+
+#[test]
+fn refracted_color_under_total_internal_reflection() {
+    let w = World::default();
+    let mut shape = w.objects[0].clone_box();
+    shape.set_material(Material { transparency: 1.0, refractive_index: 1.5, ..shape.material() });
+    let r = Ray::new(Point::new(0., 0., 2f64.sqrt() / 2.), Vector::new(0., 1., 0.));
+    let xs = Intersections::new(vec![
+        Intersection::new(-(2f64.sqrt()) / 2., shape.clone()),
+        Intersection::new(2f64.sqrt() / 2., shape.clone()),
+    ]);
+    // inside the sphere, so look at the second intersection
+    let comps = xs[1].prepare_computation_with(r, &xs);
+    assert_eq!(w.refracted_color(&comps, 5), BLACK);
+}
+
+/// The default world with a red ball below a floor
+/// made of the given [floor: Material]
+fn world_with_floor_over_ball(floor: Material) -> (World, Plane) {
+    let mut w = World::default();
+    let floor = Plane::new(Some(translate(0., -1., 0.)), Some(floor));
+    let ball = Sphere::new(
+        Some(translate(0., -3.5, -0.5)),
+        Some(Material::new(Some(Color::new(1., 0., 0.)), Some(0.5), None, None, None)),
+    );
+    w.objects.push(floor.clone_box());
+    w.objects.push(ball.clone_box());
+    w.build_bvh();
+    (w, floor)
+}
+
+#[test]
+fn shade_hit_with_transparent_material() {
+    let (w, floor) = world_with_floor_over_ball(
+        Material { transparency: 0.5, refractive_index: 1.5, ..Material::default() });
+    let r = Ray::new(Point::new(0., 0., -3.), Vector::new(0., -(2f64.sqrt()) / 2., 2f64.sqrt() / 2.));
+    let xs = Intersections::new(vec![Intersection::new(2f64.sqrt(), floor.clone_box())]);
+    let comps = xs[0].prepare_computation_with(r, &xs);
+    assert_eq!(w.shade_hit_depth(comps, 5), Color::new(0.93642, 0.68642, 0.68642));
+}
+
+#[test]
+fn shade_hit_with_reflective_transparent_material() {
+    let (w, floor) = world_with_floor_over_ball(
+        Material { reflective: 0.5, transparency: 0.5, refractive_index: 1.5, ..Material::default() });
+    let r = Ray::new(Point::new(0., 0., -3.), Vector::new(0., -(2f64.sqrt()) / 2., 2f64.sqrt() / 2.));
+    let xs = Intersections::new(vec![Intersection::new(2f64.sqrt(), floor.clone_box())]);
+    let comps = xs[0].prepare_computation_with(r, &xs);
+    assert_eq!(w.shade_hit_depth(comps, 5), Color::new(0.93391, 0.69643, 0.69243));
+}
+
+#[test]
+fn world_without_lights_renders_ambient_only() {
+    let mut w = World::default();
+    w.lights.clear();
+    let r = Ray::new(Point::new(0., 0., -5.), Vector::new(0., 0., 1.));
+    // the outer sphere's color times its ambient
+    assert_eq!(w.color_at(r), Color::new(0.08, 0.1, 0.06));
+}
+
+#[test]
+fn shading_sums_the_contribution_of_every_light() {
+    let mut w = World::default();
+    let r = Ray::new(Point::new(0., 0., -5.), Vector::new(0., 0., 1.));
+    let single = w.color_at(r);
+    w.lights.push(w.lights[0].clone());
+    // the ambient light is only added once
+    let ambient = Color::new(0.08, 0.1, 0.06);
+    assert_eq!(w.color_at(r), single * 2.0 - ambient);
+    w.lights.push(w.lights[0].clone());
+    assert_eq!(w.color_at(r), single * 3.0 - ambient * 2.0);
+}
+
+#[test]
+fn ambient_light_of_the_world() {
+    let mut w = World::default();
+    w.lights.clear();
+    w.ambient = Some(Color::new(0.5, 0.5, 0.5));
+    let r = Ray::new(Point::new(0., 0., -5.), Vector::new(0., 0., 1.));
+    assert_eq!(w.color_at(r), Color::new(0.04, 0.05, 0.03));
+}
+
+#[test]
+fn ambient_light_follows_the_lights() {
+    let mut w = World::default();
+    w.lights = vec![Box::new(Light::new(Point::new(-10., 10., -10.), Color::new(0.5, 0.25, 1.0)))];
+    let r = Ray::new(Point::new(0., 0., -5.), Vector::new(0., 0., 1.));
+    let xs = w.intersect(r);
+    let comps = xs[0].prepare_computation(r);
+    let obj = comps.object.as_ref();
+    // the world shades like the material lit by the same light
+    let expected = obj.material().lighting(
+        obj, w.lights[0].as_ref(), comps.point, comps.eyev, comps.normalv, 1.0);
+    assert_eq!(w.ambient_light(), Color::new(0.5, 0.25, 1.0));
+    assert_eq!(w.shade_hit(comps), expected);
+    // two lights share the ambient
+    w.lights.push(Box::new(Light::new(Point::new(-10., 10., -10.), Color::new(1.5, 0.75, 0.0))));
+    assert_eq!(w.ambient_light(), Color::new(1.0, 0.5, 0.5));
+}
+
+#[test]
+fn every_light_casts_its_own_shadow() {
+    let mut w = World::default();
+    // a light on the far side of the spheres
+    w.lights.push(Box::new(Light::new(Point::new(10., -10., 10.), WHITE)));
+    let p = Point::new(10., -10., 10.) * 0.5;
+    assert!(!w.is_shadowed(Point::new(10., -10., 10.), p));
+    assert!(w.is_shadowed(Point::new(-10., 10., -10.), p));
+}
+
+#[test]
+fn shadowed_lights_leave_only_the_ambient() {
+    let behind = Light::new(Point::new(0., 0., 10.), WHITE);
+    let front = Light::new(Point::new(0., 0., -10.), WHITE);
+    let s1 = Sphere::new(None, None);
+    let s2 = Sphere::new(Some(translate(0., 0., 10.)), None);
+    let r = Ray::new(Point::new(0., 0., 5.), Vector::new(0., 0., 1.));
+    let i = Intersection::new(4.0, s2.clone_box());
+    // the light in front is blocked by s1, the one behind lights s2 from the back:
+    // neither reaches the point, and the ambient is counted once, not per light
+    let w = World::new(Some(vec![Box::new(front), Box::new(behind)]), Some(vec![s1.clone_box(), s2.clone_box()]));
+    assert_eq!(w.shade_hit(i.prepare_computation(r)), Color::new(0.1, 0.1, 0.1));
+}